@@ -1,4 +1,7 @@
-use std::{collections::HashMap, ops::Range};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+};
 
 use ariadne::{Color, Label, Report, ReportKind, Source};
 use srec::{Address16, Data, Record};
@@ -196,6 +199,7 @@ pub struct AssemblyOutput {
     initialized: [bool; 256],
     first_emitted: Option<u8>,
     warnings: Vec<AssemblyWarning>,
+    labels: BTreeMap<String, u8>,
 }
 
 impl AssemblyOutput {
@@ -210,6 +214,11 @@ impl AssemblyOutput {
     pub fn warnings(&self) -> &[AssemblyWarning] {
         &self.warnings
     }
+
+    /// Address of every label in the program. `EQU` constants are not included.
+    pub fn labels(&self) -> &BTreeMap<String, u8> {
+        &self.labels
+    }
 }
 
 #[derive(Debug)]
@@ -274,6 +283,7 @@ impl Memory {
             initialized: self.initialized,
             first_emitted: self.first_emitted,
             warnings,
+            labels: BTreeMap::new(),
         }
     }
 }
@@ -285,6 +295,7 @@ pub fn assemble(src: &str, file_path: String) -> Result<AssemblyOutput, Assemble
         .map_err(AssembleError::Parse)?;
 
     let symbols = collect_symbols(&ast)?;
+    let labels = label_addresses(&ast, &symbols);
 
     let mut memory = Memory::default();
     let mut warnings = Vec::new();
@@ -396,7 +407,9 @@ pub fn assemble(src: &str, file_path: String) -> Result<AssemblyOutput, Assemble
         }
     }
 
-    Ok(memory.into_output(warnings))
+    let mut output = memory.into_output(warnings);
+    output.labels = labels;
+    Ok(output)
 }
 
 fn write_emitted_byte(
@@ -506,6 +519,28 @@ enum ResolutionState {
     Resolved(u8),
 }
 
+fn line_label(line: &AsmLine) -> Option<&String> {
+    match line {
+        AsmLine::Label { name, .. } => Some(name),
+        AsmLine::Instruction {
+            label: Some(label), ..
+        } => Some(&label.name),
+        AsmLine::Directive {
+            label: Some(label),
+            dir,
+        } if dir.name != Directive::Equ => Some(&label.name),
+        _ => None,
+    }
+}
+
+fn label_addresses(ast: &ProgramAST, symbols: &HashMap<String, u8>) -> BTreeMap<String, u8> {
+    ast.lines
+        .iter()
+        .filter_map(line_label)
+        .filter_map(|name| Some((name.to_owned(), *symbols.get(name)?)))
+        .collect()
+}
+
 fn collect_symbols(ast: &ProgramAST) -> Result<HashMap<String, u8>, AssembleError> {
     let mut declared_spans: HashMap<String, Vec<Range<usize>>> = HashMap::new();
     let mut first_duplicate = None;
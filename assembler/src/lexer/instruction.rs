@@ -82,6 +82,92 @@ pub enum Instruction {
     EXG,
 }
 
+impl Instruction {
+    /// Canonical mnemonic, as listed in the opcode table
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Self::PSHA => "PSHA",
+            Self::PSHX => "PSHX",
+            Self::PSHY => "PSHY",
+            Self::PSHC => "PSHC",
+            Self::PULA => "PULA",
+            Self::PULX => "PULX",
+            Self::PULY => "PULY",
+            Self::PULC => "PULC",
+            Self::TFR => "TFR",
+            Self::BSR => "BSR",
+            Self::BRA => "BRA",
+            Self::BMI => "BMI",
+            Self::BPL => "BPL",
+            Self::BEQ => "BEQ",
+            Self::BNE => "BNE",
+            Self::BVS => "BVS",
+            Self::BVC => "BVC",
+            Self::BCS => "BCS",
+            Self::BCC => "BCC",
+            Self::STX => "STX",
+            Self::STY => "STY",
+            Self::STSP => "STSP",
+            Self::JMP => "JMP",
+            Self::JSR => "JSR",
+            Self::CLR => "CLR",
+            Self::NEG => "NEG",
+            Self::INC => "INC",
+            Self::DEC => "DEC",
+            Self::TST => "TST",
+            Self::RTS => "RTS",
+            Self::RTI => "RTI",
+            Self::LDX => "LDX",
+            Self::LDY => "LDY",
+            Self::LDSP => "LDSP",
+            Self::SBCA => "SBCA",
+            Self::SUBA => "SUBA",
+            Self::ADCA => "ADCA",
+            Self::ADDA => "ADDA",
+            Self::CMPA => "CMPA",
+            Self::BITA => "BITA",
+            Self::ANDA => "ANDA",
+            Self::NOP => "NOP",
+            Self::LDA => "LDA",
+            Self::ANDCC => "ANDCC",
+            Self::STA => "STA",
+            Self::ORCC => "ORCC",
+            Self::CLRA => "CLRA",
+            Self::NEGA => "NEGA",
+            Self::INCA => "INCA",
+            Self::DECA => "DECA",
+            Self::TSTA => "TSTA",
+            Self::COMA => "COMA",
+            Self::BHI => "BHI",
+            Self::COM => "COM",
+            Self::ORA => "ORA",
+            Self::LSLA => "LSLA",
+            Self::BLS => "BLS",
+            Self::LSL => "LSL",
+            Self::EORA => "EORA",
+            Self::LSRA => "LSRA",
+            Self::BGT => "BGT",
+            Self::LSR => "LSR",
+            Self::CMPX => "CMPX",
+            Self::LEAX => "LEAX",
+            Self::ROLA => "ROLA",
+            Self::BGE => "BGE",
+            Self::ROL => "ROL",
+            Self::CMPY => "CMPY",
+            Self::LEAY => "LEAY",
+            Self::RORA => "RORA",
+            Self::BLE => "BLE",
+            Self::ROR => "ROR",
+            Self::CMPSP => "CMPSP",
+            Self::LEASP => "LEASP",
+            Self::ASRA => "ASRA",
+            Self::BLT => "BLT",
+            Self::ASR => "ASR",
+            Self::EXG => "EXG",
+        }
+    }
+}
+
 static INSTRUCTIONS: phf::Map<&'static str, Instruction> = phf_map! {
     "PSHA" => Instruction::PSHA,
     "PSHX" => Instruction::PSHX,
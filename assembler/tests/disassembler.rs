@@ -0,0 +1,49 @@
+use assembler::codegen::assemble;
+use flisp_core::disasm::{self, AddressingMode};
+
+/// Every opcode the disassembler knows must assemble back to the same bytes, and every
+/// other opcode must be one the assembler never emits.
+#[test]
+fn disassembly_round_trips_through_the_assembler() {
+    let mut decoded = 0;
+    for opcode in 0..=0xff_u8 {
+        let Some(ins) = disasm::decode(0x20, &[opcode, 0x05]) else {
+            continue;
+        };
+        decoded += 1;
+
+        // The lexer does not accept `,X+`-style operands yet
+        if matches!(
+            ins.mode,
+            AddressingMode::PostIncrement(_)
+                | AddressingMode::PostDecrement(_)
+                | AddressingMode::PreIncrement(_)
+                | AddressingMode::PreDecrement(_)
+        ) {
+            continue;
+        }
+
+        let src = format!("ORG $20\n{}\n", ins);
+        let output = assemble(&src, "test.sflisp".to_owned())
+            .unwrap_or_else(|_| panic!("`{}` does not assemble", ins));
+        let size = ins.size() as usize;
+        assert_eq!(
+            &output.memory()[0x20..0x20 + size],
+            &[opcode, 0x05][..size],
+            "`{}`",
+            ins
+        );
+    }
+    assert_eq!(decoded, 250);
+}
+
+#[test]
+fn branch_targets_are_resolved() {
+    let ins = disasm::decode(0x30, &[0x25, 0xfa]).unwrap();
+    assert_eq!(ins.mode, AddressingMode::Relative);
+    assert_eq!(ins.branch_target(), Some(0x2c));
+    assert_eq!(ins.to_string(), "BNE $2C");
+
+    assert!(disasm::decode(0x30, &[0x03]).is_none());
+    assert!(disasm::decode(0x30, &[0xf3]).is_none(), "operand missing");
+}
@@ -61,6 +61,22 @@ fn equ_defines_a_constant_without_emitting_bytes() {
     assert_eq!(memory.memory()[0], 0);
 }
 
+#[test]
+fn labels_exclude_equ_constants() {
+    let output = assemble(
+        "VALUE EQU $2A\nORG $20\nstart: LDA #VALUE\nloop: BRA loop\n",
+        "test.sflisp".to_owned(),
+    )
+    .unwrap();
+
+    let labels: Vec<_> = output
+        .labels()
+        .iter()
+        .map(|(n, a)| (n.as_str(), *a))
+        .collect();
+    assert_eq!(labels, [("loop", 0x22), ("start", 0x20)]);
+}
+
 #[test]
 fn equ_supports_forward_aliases() {
     let memory = assemble(
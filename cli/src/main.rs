@@ -1,8 +1,25 @@
-use std::{fs::File, path::PathBuf, process::ExitCode};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Read, Write},
+    ops::RangeInclusive,
+    path::PathBuf,
+    process::ExitCode,
+    sync::mpsc,
+};
 
 use assembler::codegen::{assemble, emit_fmem, emit_s19};
-use clap::{Parser, Subcommand, builder::OsStr};
-use emulator::Emulator;
+use clap::{Parser, Subcommand, ValueEnum, builder::OsStr};
+use emulator::{
+    Emulator,
+    breakpoint::StopReason,
+    bus::DeviceId,
+    devices::Console,
+    machine::{MACHINE_FILE, Machine},
+    protection::Protection,
+    run::RunUntil,
+    snapshot::{SNAPSHOT_EXTENSION, Snapshot},
+};
 use tui::ui::EmulatorVisualizer;
 
 use crate::fmem::parse_fmem;
@@ -11,8 +28,49 @@ use flisp_core::{fmem, s19::parse_s19};
 #[derive(Parser, Debug)]
 #[command(name = "flisp", version, about = "Unified tool for the Flisp Emulator", long_about = None)]
 enum Cli {
-    #[command(about = "Run and visualize a flisp program. Supports .sflisp, .fmem and .s19 files")]
-    Run { input: PathBuf },
+    #[command(
+        about = "Run and visualize a flisp program. Supports .sflisp, .fmem, .s19 and .fstate files"
+    )]
+    Run {
+        input: PathBuf,
+        /// File the TUI saves and loads machine state to. Defaults to <input>.fstate
+        #[arg(long)]
+        state: Option<PathBuf>,
+        /// Records every executed instruction and writes the trace on exit.
+        /// Files ending in .jsonl get JSON Lines, anything else CSV
+        #[arg(long)]
+        trace: Option<PathBuf>,
+        /// Counts executions and cycles per instruction and prints a report on exit.
+        /// Cycles are attributed to labels when running a .sflisp file
+        #[arg(long)]
+        profile: bool,
+        /// Faults when SP leaves this region, given as hex addresses like 80-df, and
+        /// prints the maximum stack depth on exit
+        #[arg(long, value_parser = parse_region)]
+        stack: Option<RangeInclusive<u8>>,
+        /// Marks a region as ROM, given like 00-7f. Writes to it are dropped and stop
+        /// execution. Can be repeated
+        #[arg(long, value_parser = parse_region)]
+        rom: Vec<RangeInclusive<u8>>,
+        /// Marks a region as code. Writes to it are logged as warnings. Can be repeated
+        #[arg(long, value_parser = parse_region)]
+        code: Vec<RangeInclusive<u8>>,
+        /// Protects every byte a .sflisp program defines, as rom or code
+        #[arg(long, value_enum)]
+        protect_program: Option<ProtectKind>,
+        /// Attaches a console with its data port at this hex address and its status
+        /// port at the next one
+        #[arg(long, value_parser = parse_console_address)]
+        console: Option<u8>,
+        /// Runs without the TUI until the program halts or faults. The console is
+        /// connected to stdin and stdout
+        #[arg(long)]
+        headless: bool,
+        /// Machine description listing memory regions and devices. Defaults to
+        /// flisp-machine.toml next to the input, if there is one
+        #[arg(long)]
+        machine: Option<PathBuf>,
+    },
     #[command(about = "Assemble your source code. Supports .sflisp files")]
     Assemble { input: PathBuf },
 }
@@ -20,12 +78,69 @@ enum Cli {
 #[derive(Subcommand, Debug)]
 enum Commands {}
 
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ProtectKind {
+    Rom,
+    Code,
+}
+
+impl From<ProtectKind> for Protection {
+    fn from(kind: ProtectKind) -> Self {
+        match kind {
+            ProtectKind::Rom => Protection::Rom,
+            ProtectKind::Code => Protection::Code,
+        }
+    }
+}
+
 fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
     let args = Cli::parse();
 
     match args {
-        Cli::Run { input } => {
-            run_visualize(input);
+        Cli::Run {
+            input,
+            state,
+            trace,
+            profile,
+            stack,
+            rom,
+            code,
+            protect_program,
+            console,
+            headless,
+            machine,
+        } => {
+            let machine_path = machine.or_else(|| {
+                let path = input.with_file_name(MACHINE_FILE);
+                path.exists().then_some(path)
+            });
+            let machine = match machine_path {
+                Some(path) => match Machine::load(&path) {
+                    Ok(machine) => machine,
+                    Err(e) => {
+                        eprintln!("Failed to load {}: {}", path.display(), e);
+                        return Ok(ExitCode::FAILURE);
+                    }
+                },
+                None => Machine::default(),
+            };
+            let options = RunOptions {
+                machine,
+                state_path: state.unwrap_or_else(|| input.with_extension(SNAPSHOT_EXTENSION)),
+                trace_path: trace,
+                profile,
+                stack_region: stack,
+                protected: rom
+                    .into_iter()
+                    .map(|range| (range, Protection::Rom))
+                    .chain(code.into_iter().map(|range| (range, Protection::Code)))
+                    .collect(),
+                protect_program: protect_program.map(Protection::from),
+                console,
+                headless,
+                labels: BTreeMap::new(),
+            };
+            run_visualize(input, options);
         }
         Cli::Assemble { input } => {
             let file = std::fs::read_to_string(input.to_string_lossy().to_string())?;
@@ -62,11 +177,69 @@ fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
     Ok(ExitCode::SUCCESS)
 }
 
-fn run_visualize(input: PathBuf) {
+struct RunOptions {
+    machine: Machine,
+    state_path: PathBuf,
+    trace_path: Option<PathBuf>,
+    profile: bool,
+    stack_region: Option<RangeInclusive<u8>>,
+    protected: Vec<(RangeInclusive<u8>, Protection)>,
+    /// Protection for the bytes an assembled program defines
+    protect_program: Option<Protection>,
+    /// Address of the console data port
+    console: Option<u8>,
+    headless: bool,
+    /// Labels from the assembler, used to attribute profile cycles
+    labels: BTreeMap<String, u8>,
+}
+
+fn parse_address(arg: &str) -> Result<u8, String> {
+    u8::from_str_radix(arg.trim_start_matches('$'), 16)
+        .map_err(|_| format!("{} is not a hex address", arg))
+}
+
+/// The console takes two addresses, so its data port cannot be the last one
+fn parse_console_address(arg: &str) -> Result<u8, String> {
+    match parse_address(arg)? {
+        0xff => Err(format!(
+            "{} leaves no address for the console status port",
+            arg
+        )),
+        adr => Ok(adr),
+    }
+}
+
+fn parse_region(arg: &str) -> Result<RangeInclusive<u8>, String> {
+    let (start, end) = arg
+        .split_once('-')
+        .ok_or_else(|| format!("expected START-END, found {}", arg))?;
+    let (start, end) = (parse_address(start)?, parse_address(end)?);
+    if start > end {
+        return Err(format!("region {} is empty", arg));
+    }
+    Ok(start..=end)
+}
+
+fn run_visualize(input: PathBuf, mut options: RunOptions) {
     let mut _file = File::open(input.clone()).expect("Failed to open file");
 
     let extension = input.extension();
 
+    if extension == Some(&OsStr::from(SNAPSHOT_EXTENSION)) {
+        let snapshot = match Snapshot::load(&input) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                eprintln!("Failed to load state: {}", e);
+                std::process::exit(1);
+            }
+        };
+        let mut program = build_emulator(&options.machine);
+        program.restore(&snapshot);
+        visualize(&mut program, options);
+        return;
+    }
+
+    let mut initialized = None;
     let mem: [u8; 256] = if input.extension() == Some(&OsStr::from("s19")) {
         match parse_s19(input) {
             Ok(mem) => mem,
@@ -83,7 +256,23 @@ fn run_visualize(input: PathBuf) {
             }
         }
     } else if extension == Some(&OsStr::from("sflisp")) {
-        todo!()
+        let src = std::fs::read_to_string(&input).expect("Failed to read file");
+        let file_path = input.to_string_lossy().to_string();
+        match assemble(&src, file_path.clone()) {
+            Ok(output) => {
+                for warning in output.warnings() {
+                    warning.report_on(&file_path, &src);
+                }
+                options.labels = output.labels().clone();
+                initialized = Some(*output.initialized());
+                *output.memory()
+            }
+            Err(error) => {
+                eprintln!("Assemble failed:");
+                error.report_on(&file_path, &src);
+                std::process::exit(1);
+            }
+        }
     } else if extension.is_some() {
         panic!(
             "Unsupported file extension: {}",
@@ -93,7 +282,146 @@ fn run_visualize(input: PathBuf) {
         panic!("Input file has no extension");
     };
 
-    let mut program = Emulator::default();
-    program.load_memory(&mem);
-    EmulatorVisualizer::viz(&mut program).unwrap()
+    let mut program = build_emulator(&options.machine);
+    match initialized {
+        Some(initialized) => program.load_image(&mem, &initialized),
+        None => program.load_memory(&mem),
+    }
+    match (options.protect_program, initialized) {
+        (Some(protection), Some(initialized)) => program.protect_bytes(&initialized, protection),
+        (Some(_), None) => eprintln!("--protect-program only applies to .sflisp files"),
+        _ => {}
+    }
+    visualize(&mut program, options);
+}
+
+fn build_emulator(machine: &Machine) -> Emulator {
+    match Emulator::with_machine(machine) {
+        Ok(program) => program,
+        Err(e) => {
+            eprintln!("Failed to set up machine: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn visualize(program: &mut Emulator, options: RunOptions) {
+    if options.trace_path.is_some() {
+        program.start_trace();
+    }
+    if options.profile {
+        program.start_profiling();
+    }
+    if let Some(region) = options.stack_region {
+        program.set_stack_region(region);
+    }
+    for (range, protection) in options.protected {
+        program.protect(range, protection);
+    }
+    let console = match options.console {
+        Some(adr) => match program.attach_device(adr..=adr + 1, Box::new(Console::default())) {
+            Ok(id) => Some(id),
+            Err(e) => {
+                eprintln!("Failed to attach console: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => program
+            .bus()
+            .devices()
+            .map(|(id, _, _)| id)
+            .find(|&id| program.bus().device_as::<Console>(id).is_some()),
+    };
+    if options.headless {
+        run_headless(program, console);
+    } else {
+        EmulatorVisualizer::viz(program, options.state_path).unwrap();
+    }
+
+    if program.stack_region().is_some() {
+        match program.max_stack_depth() {
+            Some(depth) => println!("Maximum stack depth: {} bytes", depth),
+            None => println!("SP never entered the stack region"),
+        }
+    }
+
+    if let Some(profile) = program.stop_profiling() {
+        let labels = (!options.labels.is_empty()).then_some(&options.labels);
+        profile
+            .write_report(std::io::stdout().lock(), labels)
+            .expect("Failed to write profile");
+    }
+
+    let (Some(path), Some(trace)) = (options.trace_path, program.stop_trace()) else {
+        return;
+    };
+    let result = File::create(&path).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        if path.extension() == Some(&OsStr::from("jsonl")) {
+            trace.write_jsonl(&mut out)?;
+        } else {
+            trace.write_csv(&mut out)?;
+        }
+        out.flush()
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to write trace to {}: {}", path.display(), e);
+        std::process::exit(1);
+    }
+}
+
+/// Clock cycles run between exchanges with stdin and stdout in headless mode
+const HEADLESS_CHUNK_CYCLES: u64 = 10_000;
+
+fn run_headless(program: &mut Emulator, console: Option<DeviceId>) {
+    let input = console.map(|_| spawn_stdin_reader());
+    let until = RunUntil {
+        max_cycles: Some(HEADLESS_CHUNK_CYCLES),
+        detect_halt: true,
+        ..RunUntil::default()
+    };
+    let mut stdout = std::io::stdout();
+    let reason = loop {
+        if let (Some(id), Some(input)) = (console, &input) {
+            let console = program.bus_mut().device_as_mut::<Console>(id).unwrap();
+            for bytes in input.try_iter() {
+                console.send_input(&bytes);
+            }
+        }
+        let reason = program.run_until(&until);
+        if let Some(id) = console {
+            let console = program.bus_mut().device_as_mut::<Console>(id).unwrap();
+            stdout
+                .write_all(&console.take_output())
+                .and_then(|()| stdout.flush())
+                .expect("Failed to write console output");
+        }
+        if reason != StopReason::CycleLimit {
+            break reason;
+        }
+    };
+
+    match reason {
+        StopReason::SelfBranch(pc) | StopReason::IdleLoop(pc) => {
+            eprintln!("Program halted at {:02x}", pc)
+        }
+        StopReason::Fault(error) => eprintln!("{}", error),
+        reason => eprintln!("Stopped: {:?}", reason),
+    }
+}
+
+/// Forwards stdin to the returned channel from a background thread, so the emulator
+/// never blocks waiting for input
+fn spawn_stdin_reader() -> mpsc::Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut buf = [0; 256];
+        while let Ok(n @ 1..) = stdin.read(&mut buf) {
+            if sender.send(buf[..n].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
 }
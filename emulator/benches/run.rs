@@ -0,0 +1,56 @@
+#![feature(test)]
+
+extern crate test;
+
+use emulator::Emulator;
+use test::Bencher;
+
+const BATCH: u64 = 10_000;
+
+// $20: LDA #$0a
+// $22: STA $50
+// $24: DECA
+// $25: BNE $22
+// $27: BRA $20
+fn countdown() -> Emulator {
+    let mut mem = [0; 256];
+    mem[0x20..0x29].copy_from_slice(&[0xf0, 0x0a, 0xe1, 0x50, 0x08, 0x25, 0xfb, 0x21, 0xf7]);
+    mem[0xff] = 0x20;
+    let mut emu = Emulator::default();
+    emu.load_memory(&mem);
+    emu.reset();
+    emu
+}
+
+#[bench]
+fn step(b: &mut Bencher) {
+    let mut emu = countdown();
+    b.iter(|| {
+        for _ in 0..BATCH {
+            emu.step().unwrap();
+        }
+    });
+}
+
+#[bench]
+fn step_without_history(b: &mut Bencher) {
+    let mut emu = countdown();
+    emu.set_history_budget(0);
+    b.iter(|| {
+        for _ in 0..BATCH {
+            emu.step().unwrap();
+        }
+    });
+}
+
+#[bench]
+fn run_instructions(b: &mut Bencher) {
+    let mut emu = countdown();
+    b.iter(|| emu.run_instructions(BATCH).unwrap());
+}
+
+#[bench]
+fn run_cycles(b: &mut Bencher) {
+    let mut emu = countdown();
+    b.iter(|| emu.run_cycles(4 * BATCH).unwrap());
+}
@@ -0,0 +1,18 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    /// Opcode, operand or vector byte read as part of the instruction stream
+    Fetch,
+    Read,
+    Write,
+}
+
+/// One memory access made while executing an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryAccess {
+    pub kind: AccessKind,
+    pub address: u8,
+    /// Value read, or the value stored by a write
+    pub value: u8,
+    /// Value at `address` before the access. Equal to `value` for reads and fetches.
+    pub previous: u8,
+}
@@ -0,0 +1,78 @@
+use crate::{Emulator, QState, execute::DECODED, interrupt::IRQ_CYCLES, outcome::ExecutionError};
+
+/// Work done by `run_instructions` or `run_cycles`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchRun {
+    /// Instructions executed, counting interrupt entries
+    pub instructions: u64,
+    pub cycles: u64,
+}
+
+impl Emulator {
+    /// Executes `count` instructions on the fast path.
+    ///
+    /// The fast path skips breakpoints, watchpoints, history, tracing, profiling, observer
+    /// instruction and register events, and the debug log: interrupt entries and unbalanced
+    /// returns are not logged. Faults, the stack guard, ROM protection, interrupts and the
+    /// call stack behave as in `step`.
+    ///
+    /// Returns: the work done, or the fault that stopped the run
+    pub fn run_instructions(&mut self, count: u64) -> Result<BatchRun, ExecutionError> {
+        let mut run = BatchRun::default();
+        while run.instructions < count {
+            self.step_fast(&mut run)?;
+        }
+        Ok(run)
+    }
+
+    /// Executes instructions on the fast path until at least `cycles` clock cycles have
+    /// passed. See `run_instructions` for what the fast path skips.
+    ///
+    /// Returns: the work done, or the fault that stopped the run
+    pub fn run_cycles(&mut self, cycles: u64) -> Result<BatchRun, ExecutionError> {
+        let mut run = BatchRun::default();
+        while run.cycles < cycles {
+            self.step_fast(&mut run)?;
+        }
+        Ok(run)
+    }
+
+    fn step_fast(&mut self, run: &mut BatchRun) -> Result<(), ExecutionError> {
+        let start = self.clk_count;
+        run.instructions += 1;
+        if self.q_state != QState::Fetch || self.fault.is_some() || self.micro.is_some() {
+            let result = self.step().map(drop);
+            run.cycles += (self.clk_count - start) as u64;
+            return result;
+        }
+
+        self.accesses.clear();
+        self.dropped_write = None;
+        let pc = self.reg.pc.get();
+        let sp = self.reg.sp.get();
+        let opcode = if self.interrupt_due() {
+            self.q_state = QState::Execute;
+            self.enter_interrupt();
+            self.advance_clock(IRQ_CYCLES);
+            None
+        } else {
+            let opcode = self.fetch(pc);
+            self.reg.i.set(opcode);
+            let Some(decoded) = DECODED[opcode as usize] else {
+                return Err(self.invalid_opcode(pc, opcode));
+            };
+            self.reg.pc.inc();
+            self.q_state = QState::Execute;
+            (decoded.execute)(self);
+            self.advance_clock(decoded.clocks as u32);
+            self.track_call(pc, opcode);
+            Some(opcode)
+        };
+        self.q_state = QState::Fetch;
+        run.cycles += (self.clk_count - start) as u64;
+
+        let protection = self.mark_writes(pc);
+        let stack = self.check_stack(pc, opcode, sp);
+        protection.and(stack)
+    }
+}
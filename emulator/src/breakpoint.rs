@@ -0,0 +1,510 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    CCFlag, Emulator,
+    outcome::{ExecutionError, StepOutcome},
+    watchpoint::WatchpointHit,
+};
+
+#[derive(Debug, Clone)]
+pub struct Breakpoint {
+    address: u8,
+    enabled: bool,
+    condition: Option<Condition>,
+    hit_count: u32,
+    ignore_count: u32,
+}
+
+impl Breakpoint {
+    pub fn new(address: u8) -> Self {
+        Self {
+            address,
+            enabled: true,
+            condition: None,
+            hit_count: 0,
+            ignore_count: 0,
+        }
+    }
+
+    pub fn with_condition(mut self, condition: Condition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn condition(&self) -> Option<&Condition> {
+        self.condition.as_ref()
+    }
+
+    pub fn set_condition(&mut self, condition: Option<Condition>) {
+        self.condition = condition;
+    }
+
+    /// Number of times execution reached this breakpoint with its condition satisfied
+    pub fn hit_count(&self) -> u32 {
+        self.hit_count
+    }
+
+    /// Lets the next `count` hits pass without stopping. Skipped hits are still counted.
+    pub fn set_ignore_count(&mut self, count: u32) {
+        self.ignore_count = count;
+    }
+
+    pub fn reset_hit_count(&mut self) {
+        self.hit_count = 0;
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Breakpoints {
+    entries: BTreeMap<u8, Breakpoint>,
+}
+
+impl Breakpoints {
+    /// Adds an unconditional breakpoint, replacing any existing one at `address`
+    pub fn add(&mut self, address: u8) -> &mut Breakpoint {
+        self.insert(Breakpoint::new(address))
+    }
+
+    pub fn add_conditional(&mut self, address: u8, condition: Condition) -> &mut Breakpoint {
+        self.insert(Breakpoint::new(address).with_condition(condition))
+    }
+
+    pub fn insert(&mut self, breakpoint: Breakpoint) -> &mut Breakpoint {
+        let address = breakpoint.address;
+        self.entries.insert(address, breakpoint);
+        self.entries.get_mut(&address).unwrap()
+    }
+
+    pub fn remove(&mut self, address: u8) -> Option<Breakpoint> {
+        self.entries.remove(&address)
+    }
+
+    /// Returns `false` if there is no breakpoint at `address`
+    pub fn enable(&mut self, address: u8) -> bool {
+        self.set_enabled(address, true)
+    }
+
+    /// Returns `false` if there is no breakpoint at `address`
+    pub fn disable(&mut self, address: u8) -> bool {
+        self.set_enabled(address, false)
+    }
+
+    fn set_enabled(&mut self, address: u8, enabled: bool) -> bool {
+        match self.entries.get_mut(&address) {
+            Some(bp) => {
+                bp.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds a breakpoint at `address` if there is none, otherwise removes it.
+    ///
+    /// Returns: whether a breakpoint exists at `address` afterwards
+    pub fn toggle(&mut self, address: u8) -> bool {
+        if self.entries.remove(&address).is_some() {
+            false
+        } else {
+            self.add(address);
+            true
+        }
+    }
+
+    pub fn get(&self, address: u8) -> Option<&Breakpoint> {
+        self.entries.get(&address)
+    }
+
+    pub fn get_mut(&mut self, address: u8) -> Option<&mut Breakpoint> {
+        self.entries.get_mut(&address)
+    }
+
+    pub fn contains(&self, address: u8) -> bool {
+        self.entries.contains_key(&address)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Breakpoint> {
+        self.entries.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Why `Emulator::run_until_break`, `run_until`, `step_over` or `step_out` returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// PC reached an enabled breakpoint whose condition held. The instruction has not executed yet.
+    Breakpoint(u8),
+    /// An instruction triggered a watchpoint. The instruction has completed.
+    Watchpoint(WatchpointHit),
+    /// The CPU faulted and cannot continue until reset
+    Fault(ExecutionError),
+    /// The step or instruction budget ran out
+    StepLimit,
+    /// The cycle budget ran out
+    CycleLimit,
+    /// The instruction stepped over, or the subroutine stepped out of or run until, has
+    /// returned
+    Completed,
+    /// A run until the current subroutine returns started with no subroutine call open.
+    /// Nothing was executed.
+    NotInSubroutine,
+    /// PC reached the address the run was waiting for
+    ReachedPc(u8),
+    /// The instruction at this address branched to itself, as in `BRA *`
+    SelfBranch(u8),
+    /// Execution came back to this address with registers and memory unchanged, so the
+    /// loop can never end
+    IdleLoop(u8),
+}
+
+impl Emulator {
+    pub fn breakpoints(&self) -> &Breakpoints {
+        &self.breakpoints
+    }
+
+    pub fn breakpoints_mut(&mut self) -> &mut Breakpoints {
+        &mut self.breakpoints
+    }
+
+    /// Executes instructions until a breakpoint or watchpoint is hit, the CPU faults or
+    /// `max_steps` instructions have run.
+    ///
+    /// The instruction at the current PC always executes, so calling this again after a
+    /// breakpoint stop continues past it.
+    pub fn run_until_break(&mut self, max_steps: usize) -> StopReason {
+        for _ in 0..max_steps {
+            match self.step() {
+                Ok(StepOutcome::Executed | StepOutcome::Interrupt { .. }) => {}
+                Ok(StepOutcome::BreakpointHit(address)) => return StopReason::Breakpoint(address),
+                Ok(StepOutcome::WatchpointHit(hit)) => return StopReason::Watchpoint(hit),
+                Err(error) => return StopReason::Fault(error),
+            }
+        }
+        StopReason::StepLimit
+    }
+
+    /// Evaluates the breakpoint at the current PC, updating its hit count.
+    ///
+    /// Returns: the breakpoint address if execution should stop
+    pub(crate) fn check_breakpoint(&mut self) -> Option<u8> {
+        if self.q_state == crate::QState::Reset {
+            return None;
+        }
+        let pc = self.reg.pc.get();
+        let bp = self.breakpoints.get(pc)?;
+        if !bp.enabled || !bp.condition.as_ref().is_none_or(|c| c.evaluate(self)) {
+            return None;
+        }
+
+        let bp = self.breakpoints.get_mut(pc)?;
+        bp.hit_count += 1;
+        if bp.ignore_count > 0 {
+            bp.ignore_count -= 1;
+            return None;
+        }
+        Some(pc)
+    }
+}
+
+/// Breakpoint condition such as `PC == $34 && A > $10`.
+///
+/// Operands are registers (`A`, `X`, `Y`, `SP`, `PC`, `CC`, `R`, `TA`, `LD`), flags
+/// (`I`, `N`, `Z`, `V`, `C`, evaluating to 0 or 1), memory (`M($20)`, `M(X)`) and numbers
+/// in the assembler's notation (`$2A`, `%101010`, `42`). Comparisons are unsigned.
+/// Parentheses group conditions, as in `!(Z || C)`, or enclose an operand, as in `(A) == 3`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Value(Operand),
+    Not(Box<Condition>),
+    Compare(Operand, CompareOp, Operand),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Number(u8),
+    Register(ConditionRegister),
+    Flag(ConditionFlag),
+    Memory(Box<Operand>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionRegister {
+    A,
+    X,
+    Y,
+    SP,
+    PC,
+    CC,
+    R,
+    TA,
+    LD,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionFlag {
+    I,
+    N,
+    Z,
+    V,
+    C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConditionParseError {
+    pub msg: String,
+    /// Byte offset into the condition source
+    pub position: usize,
+}
+
+impl Condition {
+    pub fn parse(src: &str) -> Result<Self, ConditionParseError> {
+        let mut parser = ConditionParser { src, pos: 0 };
+        let condition = parser.parse_or()?;
+        parser.skip_whitespace();
+        if parser.pos < src.len() {
+            return Err(parser.error("Expected end of condition"));
+        }
+        Ok(condition)
+    }
+
+    pub fn evaluate(&self, emu: &Emulator) -> bool {
+        match self {
+            Self::Value(op) => op.evaluate(emu) != 0,
+            Self::Not(inner) => !inner.evaluate(emu),
+            Self::Compare(lhs, op, rhs) => {
+                let (lhs, rhs) = (lhs.evaluate(emu), rhs.evaluate(emu));
+                match op {
+                    CompareOp::Eq => lhs == rhs,
+                    CompareOp::Ne => lhs != rhs,
+                    CompareOp::Lt => lhs < rhs,
+                    CompareOp::Le => lhs <= rhs,
+                    CompareOp::Gt => lhs > rhs,
+                    CompareOp::Ge => lhs >= rhs,
+                }
+            }
+            Self::And(lhs, rhs) => lhs.evaluate(emu) && rhs.evaluate(emu),
+            Self::Or(lhs, rhs) => lhs.evaluate(emu) || rhs.evaluate(emu),
+        }
+    }
+}
+
+impl Operand {
+    pub fn evaluate(&self, emu: &Emulator) -> u8 {
+        match self {
+            Self::Number(n) => *n,
+            Self::Register(reg) => match reg {
+                ConditionRegister::A => emu.reg.a.get(),
+                ConditionRegister::X => emu.reg.x.get(),
+                ConditionRegister::Y => emu.reg.y.get(),
+                ConditionRegister::SP => emu.reg.sp.get(),
+                ConditionRegister::PC => emu.reg.pc.get(),
+                ConditionRegister::CC => emu.reg.cc.data,
+                ConditionRegister::R => emu.reg.r.get(),
+                ConditionRegister::TA => emu.reg.ta.get(),
+                ConditionRegister::LD => emu.reg.ld.get(),
+            },
+            Self::Flag(flag) => {
+                let flag = match flag {
+                    ConditionFlag::I => CCFlag::I,
+                    ConditionFlag::N => CCFlag::N,
+                    ConditionFlag::Z => CCFlag::Z,
+                    ConditionFlag::V => CCFlag::V,
+                    ConditionFlag::C => CCFlag::C,
+                };
+                emu.reg.cc.get(flag) as u8
+            }
+            Self::Memory(adr) => emu.memory_at(adr.evaluate(emu)),
+        }
+    }
+}
+
+struct ConditionParser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl ConditionParser<'_> {
+    fn parse_or(&mut self) -> Result<Condition, ConditionParseError> {
+        let mut lhs = self.parse_and()?;
+        while self.eat("||") {
+            let rhs = self.parse_and()?;
+            lhs = Condition::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, ConditionParseError> {
+        let mut lhs = self.parse_unary()?;
+        while self.eat("&&") {
+            let rhs = self.parse_unary()?;
+            lhs = Condition::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Condition, ConditionParseError> {
+        if self.eat("!") {
+            return Ok(Condition::Not(Box::new(self.parse_unary()?)));
+        }
+        self.skip_whitespace();
+        if self.src[self.pos..].starts_with('(') {
+            // A parenthesized operand such as `(A) == 3`, otherwise a group of conditions
+            let start = self.pos;
+            if let Ok(lhs) = self.parse_operand() {
+                return self.parse_comparison(lhs);
+            }
+            self.pos = start;
+            self.eat("(");
+            let inner = self.parse_or()?;
+            if !self.eat(")") {
+                return Err(self.error("Expected `)`"));
+            }
+            return Ok(inner);
+        }
+
+        let lhs = self.parse_operand()?;
+        self.parse_comparison(lhs)
+    }
+
+    /// Parses the comparison operator and right-hand side following `lhs`, if any
+    fn parse_comparison(&mut self, lhs: Operand) -> Result<Condition, ConditionParseError> {
+        let op = if self.eat("==") {
+            CompareOp::Eq
+        } else if self.eat("!=") {
+            CompareOp::Ne
+        } else if self.eat("<=") {
+            CompareOp::Le
+        } else if self.eat(">=") {
+            CompareOp::Ge
+        } else if self.eat("<") {
+            CompareOp::Lt
+        } else if self.eat(">") {
+            CompareOp::Gt
+        } else {
+            return Ok(Condition::Value(lhs));
+        };
+        let rhs = self.parse_operand()?;
+        Ok(Condition::Compare(lhs, op, rhs))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, ConditionParseError> {
+        if self.eat("(") {
+            let inner = self.parse_operand()?;
+            if !self.eat(")") {
+                return Err(self.error("Expected `)`"));
+            }
+            return Ok(inner);
+        }
+        let start = self.pos;
+        let rest = &self.src[self.pos..];
+
+        if let Some(digits) = rest.strip_prefix('$') {
+            return self.parse_number(digits, 16, 1);
+        }
+        if let Some(digits) = rest.strip_prefix('%') {
+            return self.parse_number(digits, 2, 1);
+        }
+        if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return self.parse_number(rest, 10, 0);
+        }
+
+        let len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+        let word = &rest[..len];
+        self.pos += len;
+
+        let operand = match word.to_ascii_uppercase().as_str() {
+            "A" => Operand::Register(ConditionRegister::A),
+            "X" => Operand::Register(ConditionRegister::X),
+            "Y" => Operand::Register(ConditionRegister::Y),
+            "SP" => Operand::Register(ConditionRegister::SP),
+            "PC" => Operand::Register(ConditionRegister::PC),
+            "CC" => Operand::Register(ConditionRegister::CC),
+            "R" => Operand::Register(ConditionRegister::R),
+            "TA" => Operand::Register(ConditionRegister::TA),
+            "LD" => Operand::Register(ConditionRegister::LD),
+            "I" => Operand::Flag(ConditionFlag::I),
+            "N" => Operand::Flag(ConditionFlag::N),
+            "Z" => Operand::Flag(ConditionFlag::Z),
+            "V" => Operand::Flag(ConditionFlag::V),
+            "C" => Operand::Flag(ConditionFlag::C),
+            "M" if self.eat("(") => {
+                let adr = self.parse_operand()?;
+                if !self.eat(")") {
+                    return Err(self.error("Expected `)`"));
+                }
+                Operand::Memory(Box::new(adr))
+            }
+            _ => {
+                self.pos = start;
+                return Err(self.error("Expected a register, flag, memory reference or number"));
+            }
+        };
+        Ok(operand)
+    }
+
+    fn parse_number(
+        &mut self,
+        rest: &str,
+        radix: u32,
+        prefix_len: usize,
+    ) -> Result<Operand, ConditionParseError> {
+        let len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+        let value = u8::from_str_radix(&rest[..len], radix)
+            .map_err(|_| self.error("Invalid 8-bit number"))?;
+        self.pos += prefix_len + len;
+        Ok(Operand::Number(value))
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.src[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self, msg: &str) -> ConditionParseError {
+        ConditionParseError {
+            msg: msg.to_string(),
+            position: self.pos,
+        }
+    }
+}
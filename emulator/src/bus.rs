@@ -0,0 +1,271 @@
+use std::{any::Any, fmt, ops::RangeInclusive};
+
+use crate::{Emulator, register::Register};
+
+/// A peripheral mapped into the address space.
+///
+/// Offsets passed to the device are relative to the start of the range it is attached at.
+pub trait Device: Any + Send {
+    /// Short name shown in front ends
+    fn name(&self) -> &str;
+
+    /// Value at `offset` without side effects. Used by debuggers and memory views.
+    fn peek(&self, offset: u8) -> u8;
+
+    /// CPU read at `offset`. Devices can override this for reads with side effects,
+    /// such as clearing a status flag.
+    fn read(&mut self, offset: u8) -> u8 {
+        self.peek(offset)
+    }
+
+    fn write(&mut self, offset: u8, value: u8);
+
+    /// Called when the emulator is reset
+    fn reset(&mut self) {}
+
+    /// Called after each instruction or interrupt entry with the clock cycles it took.
+    /// Accesses made by that instruction have already been delivered.
+    fn tick(&mut self, _cycles: u32) {}
+
+    /// Whether the device is pulling the IRQ line
+    fn irq(&self) -> bool {
+        false
+    }
+
+    /// Whether the device can request an interrupt later without the CPU touching it,
+    /// as a running timer can. Halt detection is skipped while this holds.
+    fn may_interrupt(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeviceId(usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BusError {
+    /// The requested range overlaps the range of an attached device
+    Overlap {
+        existing: DeviceId,
+        range: RangeInclusive<u8>,
+    },
+}
+
+impl fmt::Display for BusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overlap { range, .. } => write!(
+                f,
+                "address range overlaps device mapped at {:02x}-{:02x}",
+                range.start(),
+                range.end()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BusError {}
+
+struct MappedDevice {
+    range: RangeInclusive<u8>,
+    device: Box<dyn Device>,
+    /// Whether the device's IRQ output reaches the CPU
+    irq_connected: bool,
+}
+
+/// The CPU's view of the address space: 256 bytes of RAM with devices mapped over parts of it.
+///
+/// Addresses without a device go to RAM. RAM behind a device is kept but not visible to the CPU.
+pub struct Bus {
+    pub(crate) ram: [Register; 256],
+    devices: Vec<Option<MappedDevice>>,
+    owner: [Option<DeviceId>; 256],
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Self {
+            ram: [Register::default(); 256],
+            devices: Vec::new(),
+            owner: [None; 256],
+        }
+    }
+}
+
+impl Bus {
+    pub fn attach(
+        &mut self,
+        range: RangeInclusive<u8>,
+        device: Box<dyn Device>,
+    ) -> Result<DeviceId, BusError> {
+        if let Some(existing) = range.clone().find_map(|adr| self.owner[adr as usize]) {
+            let range = self.range_of(existing).unwrap();
+            return Err(BusError::Overlap { existing, range });
+        }
+
+        let id = DeviceId(self.devices.len());
+        for adr in range.clone() {
+            self.owner[adr as usize] = Some(id);
+        }
+        self.devices.push(Some(MappedDevice {
+            range,
+            device,
+            irq_connected: true,
+        }));
+        Ok(id)
+    }
+
+    pub fn detach(&mut self, id: DeviceId) -> Option<Box<dyn Device>> {
+        let mapped = self.devices.get_mut(id.0)?.take()?;
+        for adr in mapped.range.clone() {
+            self.owner[adr as usize] = None;
+        }
+        Some(mapped.device)
+    }
+
+    pub fn device(&self, id: DeviceId) -> Option<&dyn Device> {
+        self.devices.get(id.0)?.as_ref().map(|m| m.device.as_ref())
+    }
+
+    pub fn device_mut(&mut self, id: DeviceId) -> Option<&mut dyn Device> {
+        self.devices
+            .get_mut(id.0)?
+            .as_mut()
+            .map(|m| m.device.as_mut())
+    }
+
+    /// The device `id` downcast to its concrete type
+    pub fn device_as<T: Device>(&self, id: DeviceId) -> Option<&T> {
+        let device: &dyn Any = self.device(id)?;
+        device.downcast_ref()
+    }
+
+    pub fn device_as_mut<T: Device>(&mut self, id: DeviceId) -> Option<&mut T> {
+        let device: &mut dyn Any = self.device_mut(id)?;
+        device.downcast_mut()
+    }
+
+    /// The device mapped at `adr`, if any
+    pub fn device_at(&self, adr: u8) -> Option<DeviceId> {
+        self.owner[adr as usize]
+    }
+
+    pub fn range_of(&self, id: DeviceId) -> Option<RangeInclusive<u8>> {
+        self.devices.get(id.0)?.as_ref().map(|m| m.range.clone())
+    }
+
+    pub fn devices(&self) -> impl Iterator<Item = (DeviceId, RangeInclusive<u8>, &dyn Device)> {
+        self.devices.iter().enumerate().filter_map(|(i, m)| {
+            m.as_ref()
+                .map(|m| (DeviceId(i), m.range.clone(), m.device.as_ref()))
+        })
+    }
+
+    pub fn read(&mut self, adr: u8) -> u8 {
+        match self.mapped_mut(adr) {
+            Some((device, offset)) => device.read(offset),
+            None => self.ram[adr as usize].get(),
+        }
+    }
+
+    pub fn write(&mut self, adr: u8, value: u8) {
+        match self.mapped_mut(adr) {
+            Some((device, offset)) => device.write(offset, value),
+            None => self.ram[adr as usize].set(value),
+        }
+    }
+
+    pub fn peek(&self, adr: u8) -> u8 {
+        match self.owner[adr as usize] {
+            Some(id) => {
+                let mapped = self.devices[id.0].as_ref().unwrap();
+                mapped.device.peek(adr - mapped.range.start())
+            }
+            None => self.ram[adr as usize].get(),
+        }
+    }
+
+    /// Connects or disconnects the IRQ output of device `id`. Devices are connected when
+    /// attached.
+    ///
+    /// Returns: `false` if no device `id` is attached
+    pub fn set_irq_connected(&mut self, id: DeviceId, connected: bool) -> bool {
+        match self.devices.get_mut(id.0) {
+            Some(Some(mapped)) => {
+                mapped.irq_connected = connected;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn irq_connected(&self, id: DeviceId) -> Option<bool> {
+        self.devices.get(id.0)?.as_ref().map(|m| m.irq_connected)
+    }
+
+    /// Whether any attached device is requesting an interrupt
+    pub fn irq_asserted(&self) -> bool {
+        self.connected().any(|device| device.irq())
+    }
+
+    /// Whether a connected device may raise an interrupt without the CPU touching it
+    pub(crate) fn may_interrupt(&self) -> bool {
+        self.connected().any(|device| device.may_interrupt())
+    }
+
+    /// Writes RAM directly, bypassing devices. Used to rewind history, which does not
+    /// rewind device state.
+    pub(crate) fn poke_ram(&mut self, adr: u8, value: u8) {
+        if self.owner[adr as usize].is_none() {
+            self.ram[adr as usize].set(value);
+        }
+    }
+
+    pub(crate) fn tick_devices(&mut self, cycles: u32) {
+        for mapped in self.devices.iter_mut().flatten() {
+            mapped.device.tick(cycles);
+        }
+    }
+
+    pub(crate) fn reset_devices(&mut self) {
+        for mapped in self.devices.iter_mut().flatten() {
+            mapped.device.reset();
+        }
+    }
+
+    /// Devices whose IRQ output is connected
+    fn connected(&self) -> impl Iterator<Item = &dyn Device> {
+        self.devices
+            .iter()
+            .flatten()
+            .filter(|m| m.irq_connected)
+            .map(|m| m.device.as_ref())
+    }
+
+    fn mapped_mut(&mut self, adr: u8) -> Option<(&mut dyn Device, u8)> {
+        let id = self.owner[adr as usize]?;
+        let mapped = self.devices[id.0].as_mut().unwrap();
+        let offset = adr - mapped.range.start();
+        Some((mapped.device.as_mut(), offset))
+    }
+}
+
+impl Emulator {
+    pub fn bus(&self) -> &Bus {
+        &self.bus
+    }
+
+    pub fn bus_mut(&mut self) -> &mut Bus {
+        &mut self.bus
+    }
+
+    /// Maps `device` over `range`. CPU accesses to those addresses go to the device instead
+    /// of RAM.
+    pub fn attach_device(
+        &mut self,
+        range: RangeInclusive<u8>,
+        device: Box<dyn Device>,
+    ) -> Result<DeviceId, BusError> {
+        self.bus.attach(range, device)
+    }
+}
@@ -0,0 +1,97 @@
+use flisp_core::opcode;
+
+use crate::{Emulator, breakpoint::StopReason, outcome::StepOutcome};
+
+/// Opcodes that call a subroutine: BSR and every JSR addressing mode
+pub(crate) const CALL_OPCODES: [u8; 6] = [0x20, 0x34, 0x54, 0x64, 0x74, 0x84];
+pub(crate) const RTS: u8 = 0x43;
+
+/// A subroutine call that has not returned yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallFrame {
+    /// Address of the BSR or JSR
+    pub call_site: u8,
+    /// Address of the subroutine
+    pub target: u8,
+    /// Address pushed for RTS to return to
+    pub return_address: u8,
+    /// SP at entry, pointing at the pushed return address
+    pub sp: u8,
+}
+
+impl Emulator {
+    /// Subroutine calls made by BSR and JSR that have not returned, outermost first.
+    /// Interrupts are not recorded.
+    pub fn call_stack(&self) -> &[CallFrame] {
+        &self.call_stack
+    }
+
+    /// Executes one instruction. A BSR or JSR runs until its subroutine returns.
+    ///
+    /// Returns: `Completed`, or why execution stopped early. At most `max_steps`
+    /// instructions run.
+    pub fn step_over(&mut self, max_steps: usize) -> StopReason {
+        let depth = self.call_stack.len();
+        self.run_while(max_steps, |emu| emu.call_stack.len() > depth)
+    }
+
+    /// Runs until the innermost subroutine returns. Without a recorded call, nothing
+    /// is executed.
+    ///
+    /// Returns: `Completed`, or why execution stopped early. At most `max_steps`
+    /// instructions run.
+    pub fn step_out(&mut self, max_steps: usize) -> StopReason {
+        let depth = self.call_stack.len();
+        if depth == 0 {
+            return StopReason::Completed;
+        }
+        self.run_while(max_steps, |emu| emu.call_stack.len() >= depth)
+    }
+
+    fn run_while(&mut self, max_steps: usize, running: impl Fn(&Self) -> bool) -> StopReason {
+        for _ in 0..max_steps {
+            match self.step() {
+                Ok(StepOutcome::Executed | StepOutcome::Interrupt { .. }) => {}
+                Ok(StepOutcome::BreakpointHit(address)) => return StopReason::Breakpoint(address),
+                Ok(StepOutcome::WatchpointHit(hit)) => return StopReason::Watchpoint(hit),
+                Err(error) => return StopReason::Fault(error),
+            }
+            if !running(self) {
+                return StopReason::Completed;
+            }
+        }
+        StopReason::StepLimit
+    }
+
+    /// Pushes a frame for a call or pops one for RTS, after `opcode` at `pc` has executed
+    ///
+    /// Returns: the frame popped by RTS
+    pub(crate) fn track_call(&mut self, pc: u8, opcode: u8) -> Option<CallFrame> {
+        if CALL_OPCODES.contains(&opcode) {
+            let size = opcode::info(opcode).map_or(1, |info| info.bytes);
+            self.call_stack.push(CallFrame {
+                call_site: pc,
+                target: self.reg.pc.get(),
+                return_address: pc.wrapping_add(size),
+                sp: self.reg.sp.get(),
+            });
+            return None;
+        }
+        if opcode != RTS {
+            return None;
+        }
+        self.call_stack.pop()
+    }
+
+    /// Logs an RTS at `pc` that returned somewhere other than the address `frame` recorded,
+    /// which usually means unbalanced pushes and pulls inside the subroutine
+    pub(crate) fn check_return(&mut self, pc: u8, frame: &CallFrame) {
+        let returned_to = self.reg.pc.get();
+        if returned_to != frame.return_address {
+            self.debug_log(format!(
+                "RTS at {:02x} returned to {:02x}, expected {:02x} for call at {:02x}",
+                pc, returned_to, frame.return_address, frame.call_site
+            ));
+        }
+    }
+}
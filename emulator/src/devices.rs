@@ -0,0 +1,17 @@
+mod console;
+mod keypad;
+mod leds;
+mod seven_segment;
+mod switches;
+mod timer;
+
+pub use console::{CONSOLE_INPUT_READY, CONSOLE_OUTPUT_READY, Console};
+pub use keypad::{KEYPAD_READY, Keypad};
+pub use leds::{LedChange, Leds};
+pub use seven_segment::{
+    DECIMAL_POINT, DigitCountError, MAX_DIGITS, SevenSegment, decode_segments,
+};
+pub use switches::Switches;
+pub use timer::{
+    TIMER_ENABLE, TIMER_EXPIRED, TIMER_IRQ_ENABLE, TIMER_ONE_SHOT, TIMER_PRESCALE, Timer,
+};
@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+
+use crate::bus::Device;
+
+/// Console status bit set while input is waiting to be read
+pub const CONSOLE_INPUT_READY: u8 = 0x80;
+/// Console status bit set when a byte can be written. The console never stalls, so this
+/// is always set.
+pub const CONSOLE_OUTPUT_READY: u8 = 0x40;
+
+/// Character I/O mapped at two addresses:
+///
+/// - offset 0: data. Writing sends a byte to the output; reading takes the next input
+///   byte, or 0 when there is none.
+/// - offset 1: status, see `CONSOLE_INPUT_READY` and `CONSOLE_OUTPUT_READY`
+///
+/// The host feeds input with `send_input` and collects output with `output` or
+/// `take_output`, which lets the console be bridged to a terminal, a TUI pane or a test.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Console {
+    /// Input queued again on every reset
+    initial_input: Vec<u8>,
+    input: VecDeque<u8>,
+    output: Vec<u8>,
+}
+
+impl Console {
+    /// A console with `input` waiting to be read, again after every reset
+    pub fn with_input(input: &[u8]) -> Self {
+        Self {
+            initial_input: input.to_vec(),
+            input: input.iter().copied().collect(),
+            output: Vec::new(),
+        }
+    }
+
+    /// Queues `bytes` for the program to read
+    pub fn send_input(&mut self, bytes: &[u8]) {
+        self.input.extend(bytes);
+    }
+
+    /// Whether input is waiting to be read by the program
+    pub fn has_input(&self) -> bool {
+        !self.input.is_empty()
+    }
+
+    /// Everything written since the last reset or `take_output`
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.output)
+    }
+}
+
+impl Device for Console {
+    fn name(&self) -> &str {
+        "console"
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        match offset {
+            0 => self.input.front().copied().unwrap_or(0),
+            1 if self.has_input() => CONSOLE_INPUT_READY | CONSOLE_OUTPUT_READY,
+            1 => CONSOLE_OUTPUT_READY,
+            _ => 0,
+        }
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        if offset == 0 {
+            return self.input.pop_front().unwrap_or(0);
+        }
+        self.peek(offset)
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        if offset == 0 {
+            self.output.push(value);
+        }
+    }
+
+    /// Discards output and input typed since, leaving the initial input to be read
+    fn reset(&mut self) {
+        self.input = self.initial_input.iter().copied().collect();
+        self.output.clear();
+    }
+}
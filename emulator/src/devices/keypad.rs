@@ -0,0 +1,63 @@
+use crate::bus::Device;
+
+/// Bit of the keypad status register set while a key is waiting to be read
+pub const KEYPAD_READY: u8 = 0x80;
+
+/// A hex keypad with a one key latch, mapped at two addresses:
+///
+/// - offset 0: code of the latched key, 0-f. Reading it releases the latch.
+/// - offset 1: status, with `KEYPAD_READY` set while a key is latched
+///
+/// Keys pressed while one is latched are lost, as on the hardware.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Keypad {
+    key: u8,
+    ready: bool,
+}
+
+impl Keypad {
+    /// Presses `key`, 0-f. Higher bits are ignored.
+    ///
+    /// Returns: `false` if the key was lost because another was still latched
+    pub fn press(&mut self, key: u8) -> bool {
+        if self.ready {
+            return false;
+        }
+        self.key = key & 0x0f;
+        self.ready = true;
+        true
+    }
+
+    /// The key waiting to be read by the program
+    pub fn pending(&self) -> Option<u8> {
+        self.ready.then_some(self.key)
+    }
+}
+
+impl Device for Keypad {
+    fn name(&self) -> &str {
+        "keypad"
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        match offset {
+            0 => self.key,
+            1 if self.ready => KEYPAD_READY,
+            _ => 0,
+        }
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        let value = self.peek(offset);
+        if offset == 0 {
+            self.ready = false;
+        }
+        value
+    }
+
+    fn write(&mut self, _offset: u8, _value: u8) {}
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -0,0 +1,75 @@
+use crate::bus::Device;
+
+/// The LED bank changing to `value`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedChange {
+    /// Clock cycles the bank had been ticked since its own reset when the value changed.
+    /// This is the device's count, not the emulator's `clk_count`: it starts when the bank
+    /// is attached and is not rewound by `step_back` or restoring a snapshot.
+    pub ticks: u64,
+    pub value: u8,
+}
+
+/// A bank of eight LEDs driven by an output port. Bit n lights LED n. Reads return the
+/// last value written, and every address in the mapped range drives the same bank.
+///
+/// Each change of the output is recorded with the bank's own tick count, so a front end
+/// or test can follow the LEDs over time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Leds {
+    value: u8,
+    /// Clock cycles ticked since the last reset
+    ticks: u64,
+    changes: Vec<LedChange>,
+}
+
+impl Leds {
+    /// The LEDs currently lit
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    /// Whether LED `bit` (0-7) is lit
+    pub fn is_on(&self, bit: u8) -> bool {
+        self.value & (1 << bit) != 0
+    }
+
+    /// Every change of the output since the last reset or `clear_changes`, oldest first.
+    /// Writes of the value already shown are not recorded.
+    pub fn changes(&self) -> &[LedChange] {
+        &self.changes
+    }
+
+    pub fn clear_changes(&mut self) {
+        self.changes.clear();
+    }
+}
+
+impl Device for Leds {
+    fn name(&self) -> &str {
+        "leds"
+    }
+
+    fn peek(&self, _offset: u8) -> u8 {
+        self.value
+    }
+
+    fn write(&mut self, _offset: u8, value: u8) {
+        if value == self.value {
+            return;
+        }
+        self.value = value;
+        self.changes.push(LedChange {
+            ticks: self.ticks,
+            value,
+        });
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn tick(&mut self, cycles: u32) {
+        self.ticks += cycles as u64;
+    }
+}
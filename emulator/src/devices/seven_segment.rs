@@ -0,0 +1,141 @@
+use std::fmt;
+
+use crate::bus::Device;
+
+/// Segment patterns for 0-9 and A-F, bit 0 is segment a and bit 6 segment g
+const HEX_DIGITS: [u8; 16] = [
+    0x3f, 0x06, 0x5b, 0x4f, 0x66, 0x6d, 0x7d, 0x07, 0x7f, 0x6f, 0x77, 0x7c, 0x39, 0x5e, 0x79, 0x71,
+];
+
+/// Bit of a segment pattern that lights the decimal point
+pub const DECIMAL_POINT: u8 = 0x80;
+
+/// Most digits a display can have, one per bit of the digit select register
+pub const MAX_DIGITS: usize = 8;
+
+/// A display size outside 1 to `MAX_DIGITS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigitCountError(pub usize);
+
+impl fmt::Display for DigitCountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a display has 1 to {} digits, not {}",
+            MAX_DIGITS, self.0
+        )
+    }
+}
+
+impl std::error::Error for DigitCountError {}
+
+/// Decodes the segments in `pattern`, ignoring the decimal point.
+///
+/// Returns: the hex digit shown, `' '` for a blank digit, `'-'` for segment g alone, or
+/// `None` for any other pattern
+pub fn decode_segments(pattern: u8) -> Option<char> {
+    let segments = pattern & !DECIMAL_POINT;
+    match segments {
+        0x00 => Some(' '),
+        0x40 => Some('-'),
+        _ => HEX_DIGITS
+            .iter()
+            .position(|&digit| digit == segments)
+            .and_then(|value| char::from_digit(value as u32, 16))
+            .map(|c| c.to_ascii_uppercase()),
+    }
+}
+
+/// A multiplexed display of up to eight seven-segment digits, mapped at two addresses:
+///
+/// - offset 0: segment pattern, bit 0-6 for segments a-g and bit 7 for the decimal point
+/// - offset 1: digit select, bit n drives digit n counting from the left
+///
+/// Selected digits show the segment pattern. A digit keeps its last pattern after being
+/// deselected, as it appears to the eye when the program multiplexes fast enough.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SevenSegment {
+    segments: u8,
+    select: u8,
+    digits: Vec<u8>,
+}
+
+impl SevenSegment {
+    /// A blank display with `digits` digits
+    ///
+    /// Returns: an error unless `digits` is 1 to `MAX_DIGITS`
+    pub fn new(digits: usize) -> Result<Self, DigitCountError> {
+        if !(1..=MAX_DIGITS).contains(&digits) {
+            return Err(DigitCountError(digits));
+        }
+        Ok(Self::blank(digits))
+    }
+
+    fn blank(digits: usize) -> Self {
+        Self {
+            segments: 0,
+            select: 0,
+            digits: vec![0; digits],
+        }
+    }
+
+    /// Segment pattern shown by each digit, from the left
+    pub fn digits(&self) -> &[u8] {
+        &self.digits
+    }
+
+    /// The display as text, with undecodable digits shown as `?` and each lit decimal
+    /// point as `.` after its digit
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for &pattern in &self.digits {
+            text.push(decode_segments(pattern).unwrap_or('?'));
+            if pattern & DECIMAL_POINT != 0 {
+                text.push('.');
+            }
+        }
+        text
+    }
+
+    fn latch_selected(&mut self) {
+        for (n, digit) in self.digits.iter_mut().enumerate() {
+            if self.select & (1 << n) != 0 {
+                *digit = self.segments;
+            }
+        }
+    }
+}
+
+impl Default for SevenSegment {
+    /// Four digits, as on the lab board
+    fn default() -> Self {
+        Self::blank(4)
+    }
+}
+
+impl Device for SevenSegment {
+    fn name(&self) -> &str {
+        "7-segment"
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        match offset {
+            0 => self.segments,
+            1 => self.select,
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        match offset {
+            0 => self.segments = value,
+            1 => self.select = value,
+            _ => return,
+        }
+        self.latch_selected();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::blank(self.digits.len());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::bus::Device;
+
+/// A bank of eight DIP switches, read as one byte. Bit n is switch n, set when the switch
+/// is on. CPU writes are ignored, and every address in the mapped range reads the bank.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Switches {
+    value: u8,
+}
+
+impl Switches {
+    pub fn new(value: u8) -> Self {
+        Self { value }
+    }
+
+    /// Positions of all eight switches
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    pub fn set(&mut self, value: u8) {
+        self.value = value;
+    }
+
+    /// Whether switch `bit` (0-7) is on
+    pub fn is_on(&self, bit: u8) -> bool {
+        self.value & (1 << bit) != 0
+    }
+
+    /// Turns switch `bit` (0-7) on or off
+    pub fn set_switch(&mut self, bit: u8, on: bool) {
+        if on {
+            self.value |= 1 << bit;
+        } else {
+            self.value &= !(1 << bit);
+        }
+    }
+
+    pub fn toggle(&mut self, bit: u8) {
+        self.value ^= 1 << bit;
+    }
+}
+
+impl Device for Switches {
+    fn name(&self) -> &str {
+        "switches"
+    }
+
+    fn peek(&self, _offset: u8) -> u8 {
+        self.value
+    }
+
+    fn write(&mut self, _offset: u8, _value: u8) {}
+}
@@ -0,0 +1,141 @@
+use crate::bus::Device;
+
+/// Control bit that starts the timer. Setting it loads the counter from the reload register.
+pub const TIMER_ENABLE: u8 = 0x01;
+/// Control bit that lets an expired timer request an interrupt
+pub const TIMER_IRQ_ENABLE: u8 = 0x02;
+/// Control bit that stops the timer at its first expiry instead of reloading
+pub const TIMER_ONE_SHOT: u8 = 0x04;
+/// Control bits 4-5 select the clock cycles per count: 1, 8, 64 or 256
+pub const TIMER_PRESCALE: u8 = 0x30;
+/// Status bit set when the counter expires. Writing it back as 1 clears it.
+pub const TIMER_EXPIRED: u8 = 0x80;
+
+/// A down-counting timer driven by the emulator clock, mapped at four addresses:
+///
+/// - offset 0: control, see `TIMER_ENABLE`, `TIMER_IRQ_ENABLE`, `TIMER_ONE_SHOT` and
+///   `TIMER_PRESCALE`
+/// - offset 1: reload value, the period in counts. 0 counts 256.
+/// - offset 2: status, see `TIMER_EXPIRED`
+/// - offset 3: current count, read only
+///
+/// The timer counts after each instruction by the cycles it took, so it expires on an
+/// instruction boundary and the same program always sees it expire at the same clock
+/// count. While the expired flag is set and interrupts are enabled in control, the timer
+/// holds the IRQ line asserted; the handler releases it by clearing the flag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timer {
+    control: u8,
+    reload: u8,
+    expired: bool,
+    /// Counts left until expiry, 1-256 while running
+    counter: u16,
+    /// Cycles counted towards the next count
+    prescaled: u32,
+    expirations: u64,
+}
+
+impl Timer {
+    /// Clock cycles from starting the timer to its first expiry, and between expiries
+    pub fn period_cycles(&self) -> u32 {
+        self.period() as u32 * self.prescale()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.control & TIMER_ENABLE != 0
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expired
+    }
+
+    /// Counts left until the next expiry
+    pub fn counter(&self) -> u16 {
+        self.counter
+    }
+
+    /// Times the counter has expired since the last reset
+    pub fn expirations(&self) -> u64 {
+        self.expirations
+    }
+
+    fn period(&self) -> u16 {
+        if self.reload == 0 {
+            256
+        } else {
+            self.reload as u16
+        }
+    }
+
+    fn prescale(&self) -> u32 {
+        1 << (3 * ((self.control & TIMER_PRESCALE) >> 4))
+    }
+
+    fn expire(&mut self) {
+        self.expired = true;
+        self.expirations += 1;
+        if self.control & TIMER_ONE_SHOT != 0 {
+            self.control &= !TIMER_ENABLE;
+        }
+        self.counter = self.period();
+    }
+}
+
+impl Device for Timer {
+    fn name(&self) -> &str {
+        "timer"
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        match offset {
+            0 => self.control,
+            1 => self.reload,
+            2 if self.expired => TIMER_EXPIRED,
+            3 => self.counter as u8,
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        match offset {
+            0 => {
+                if value & TIMER_ENABLE != 0 && !self.is_running() {
+                    self.counter = self.period();
+                    self.prescaled = 0;
+                }
+                self.control = value;
+            }
+            1 => self.reload = value,
+            2 if value & TIMER_EXPIRED != 0 => self.expired = false,
+            _ => {}
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn irq(&self) -> bool {
+        self.expired && self.control & TIMER_IRQ_ENABLE != 0
+    }
+
+    fn may_interrupt(&self) -> bool {
+        self.control & TIMER_IRQ_ENABLE != 0 && (self.is_running() || self.expired)
+    }
+
+    fn tick(&mut self, cycles: u32) {
+        let prescale = self.prescale();
+        self.prescaled += cycles;
+        while self.is_running() && self.prescaled >= prescale {
+            let counts = (self.prescaled / prescale).min(self.counter as u32);
+            self.prescaled -= counts * prescale;
+            self.counter -= counts as u16;
+            if self.counter == 0 {
+                self.expire();
+            }
+        }
+        if !self.is_running() {
+            self.prescaled = 0;
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{
+    CCFlag, Emulator, QState,
+    register::{InternalRegister, VisibleRegister},
+};
+
+/// A change made through the editing API rather than by the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Register {
+        register: VisibleRegister,
+        old: u8,
+        new: u8,
+    },
+    InternalRegister {
+        register: InternalRegister,
+        old: u8,
+        new: u8,
+    },
+    Memory {
+        address: u8,
+        old: u8,
+        new: u8,
+    },
+    QState {
+        old: QState,
+        new: QState,
+    },
+}
+
+/// An edit and the clock count at which it was made
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditRecord {
+    pub clk_count: u32,
+    pub edit: Edit,
+}
+
+impl Emulator {
+    /// Changes made through the editing API since the last reset, oldest first.
+    ///
+    /// Edits are kept apart from execution: they do not create history entries, do not
+    /// appear in traces and are not reported to observers as memory accesses.
+    pub fn edits(&self) -> &[EditRecord] {
+        &self.edits
+    }
+
+    pub fn clear_edits(&mut self) {
+        self.edits.clear();
+    }
+
+    pub fn register(&self, register: VisibleRegister) -> u8 {
+        match register {
+            VisibleRegister::A => self.reg.a.get(),
+            VisibleRegister::X => self.reg.x.get(),
+            VisibleRegister::Y => self.reg.y.get(),
+            VisibleRegister::SP => self.reg.sp.get(),
+            VisibleRegister::PC => self.reg.pc.get(),
+            VisibleRegister::CC => self.reg.cc.data(),
+        }
+    }
+
+    pub fn set_register(&mut self, register: VisibleRegister, value: u8) {
+        self.begin_edit();
+        let old = self.register(register);
+        match register {
+            VisibleRegister::A => self.reg.a.set(value),
+            VisibleRegister::X => self.reg.x.set(value),
+            VisibleRegister::Y => self.reg.y.set(value),
+            VisibleRegister::SP => self.reg.sp.set(value),
+            VisibleRegister::PC => self.reg.pc.set(value),
+            VisibleRegister::CC => self.reg.cc.overwrite(value),
+        }
+        self.record_edit(Edit::Register {
+            register,
+            old,
+            new: value,
+        });
+    }
+
+    pub fn internal_register(&self, register: InternalRegister) -> u8 {
+        match register {
+            InternalRegister::R => self.reg.r.get(),
+            InternalRegister::TA => self.reg.ta.get(),
+            InternalRegister::LD => self.reg.ld.get(),
+            InternalRegister::I => self.reg.i.get(),
+        }
+    }
+
+    /// Sets a datapath register. The next instruction overwrites I when it is fetched,
+    /// and R, TA and LD as it accesses memory.
+    pub fn set_internal_register(&mut self, register: InternalRegister, value: u8) {
+        self.begin_edit();
+        let old = self.internal_register(register);
+        match register {
+            InternalRegister::R => self.reg.r.set(value),
+            InternalRegister::TA => self.reg.ta.set(value),
+            InternalRegister::LD => self.reg.ld.set(value),
+            InternalRegister::I => self.reg.i.set(value),
+        }
+        self.record_edit(Edit::InternalRegister {
+            register,
+            old,
+            new: value,
+        });
+    }
+
+    pub fn set_flag(&mut self, flag: CCFlag, value: bool) {
+        let mut cc = self.reg.cc;
+        cc.set(flag, value);
+        self.set_register(VisibleRegister::CC, cc.data());
+    }
+
+    /// Stores `value` at `adr` as the CPU would, so a mapped device receives it. The byte
+    /// counts as initialized until the next reset.
+    pub fn write_memory(&mut self, adr: u8, value: u8) {
+        self.begin_edit();
+        let old = self.bus.peek(adr);
+        self.bus.write(adr, value);
+        self.edited[adr as usize] = true;
+        self.record_edit(Edit::Memory {
+            address: adr,
+            old,
+            new: value,
+        });
+    }
+
+    /// Writes `bytes` from `start` onwards, wrapping at the end of memory
+    pub fn write_memory_range(&mut self, start: u8, bytes: &[u8]) {
+        for (offset, value) in bytes.iter().enumerate() {
+            self.write_memory(start.wrapping_add(offset as u8), *value);
+        }
+    }
+
+    /// Sets the state the control unit is in between instructions.
+    ///
+    /// Returns: `false` for `Execute`, which only occurs part way through a micro-stepped
+    /// instruction
+    pub fn set_q_state(&mut self, q_state: QState) -> bool {
+        if q_state == QState::Execute {
+            return false;
+        }
+        self.begin_edit();
+        let old = self.q_state;
+        self.q_state = q_state;
+        self.record_edit(Edit::QState { old, new: q_state });
+        true
+    }
+
+    /// Abandons a micro-stepped instruction so the edit is not overwritten by its last clock
+    fn begin_edit(&mut self) {
+        if self.instruction_clock().is_some() {
+            self.step_back();
+        }
+    }
+
+    fn record_edit(&mut self, edit: Edit) {
+        self.edits.push(EditRecord {
+            clk_count: self.clk_count,
+            edit,
+        });
+    }
+}
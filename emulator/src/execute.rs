@@ -0,0 +1,1796 @@
+use std::sync::LazyLock;
+
+use flisp_core::opcode;
+
+use crate::{
+    CCFlag, Emulator,
+    math_utils::{add, add_c, rotate_left, rotate_right, shl, shr, shr_signed, sub, sub_c},
+};
+
+/// Carries out one instruction. Called with the opcode in I and PC just past it. Operand
+/// bytes are read with `fetch_operand`, which leaves PC on the next instruction.
+pub(crate) type Execute = fn(&mut Emulator);
+
+/// An opcode resolved to everything needed to run it
+#[derive(Clone, Copy)]
+pub(crate) struct Decoded {
+    pub(crate) execute: Execute,
+    pub(crate) clocks: u8,
+}
+
+/// Decoded instructions indexed by opcode, `None` for invalid opcodes. Timing comes from
+/// the shared opcode table.
+pub(crate) static DECODED: LazyLock<[Option<Decoded>; 256]> = LazyLock::new(|| {
+    std::array::from_fn(|op| {
+        Some(Decoded {
+            execute: EXECUTE[op]?,
+            clocks: opcode::info(op as u8)?.clocks,
+        })
+    })
+});
+
+macro_rules! instructions {
+    ($($opcode:literal => |$emu:ident| $body:block),* $(,)?) => {{
+        let mut table: [Option<Execute>; 256] = [None; 256];
+        $(table[$opcode] = Some(|$emu: &mut Emulator| $body);)*
+        table
+    }};
+}
+
+static EXECUTE: [Option<Execute>; 256] = instructions! {
+    0x00 => |_emu| {
+        // NOP
+    },
+    0x01 => |emu| {
+        // ANDCC #Data
+        let data = emu.fetch_operand();
+        let result = emu.reg.cc.data & data;
+        emu.reg.cc.overwrite(result);
+    },
+    0x02 => |emu| {
+        // ORCC #Data
+        let data = emu.fetch_operand();
+        let result = emu.reg.cc.data | data;
+        emu.reg.cc.overwrite(result);
+    },
+    0x05 => |emu| {
+        // CLRA
+        emu.reg.a.set(0);
+        emu.set_clr_flags();
+    },
+    0x06 => |emu| {
+        // NEGA
+        let (new_a, _c, v) = sub(0, emu.reg.a.get());
+        emu.set_neg_flags(new_a, emu.reg.a.get(), v);
+        emu.reg.a.set(new_a);
+    },
+    0x07 => |emu| {
+        // INCA
+        let (_c, v) = emu.reg.a.inc();
+        let new_a = emu.reg.a.get();
+        emu.set_inc_flags(new_a, v);
+    },
+    0x08 => |emu| {
+        // DECA
+        let (_c, v) = emu.reg.a.dec();
+        emu.set_dec_flags(emu.reg.a.get(), v);
+    },
+    0x09 => |emu| {
+        // TSTA
+        emu.set_tst_flags(emu.reg.a.get());
+    },
+    0x0a => |emu| {
+        // COMA
+        let new_a = !emu.reg.a.get();
+        emu.reg.a.set(new_a);
+        emu.set_com_flags(new_a);
+    },
+    0x0b => |emu| {
+        // ASLA / LSLA
+        let (new_a, c, v) = shl(emu.reg.a);
+        emu.reg.a.set(new_a);
+        emu.set_asl_flags(new_a, c, v);
+    },
+    0x0c => |emu| {
+        // LSRA
+        let (new_a, c, v) = shr(emu.reg.a);
+        emu.reg.a.set(new_a);
+        emu.set_lsr_flags(new_a, c, v);
+    },
+    0x0d => |emu| {
+        // ROLA
+        let (new_a, c) = rotate_left(emu.reg.a);
+        emu.reg.a.set(new_a);
+        emu.set_rol_flags(new_a, c);
+    },
+    0x0e => |emu| {
+        // RORA
+        let (new_a, c) = rotate_right(emu.reg.a);
+        emu.reg.a.set(new_a);
+        emu.set_ror_flags(new_a, c);
+    },
+    0x0f => |emu| {
+        // ASRA
+        let (new_a, c) = shr_signed(emu.reg.a.get());
+        emu.reg.a.set(new_a);
+        emu.set_asr_flags(new_a, c);
+    },
+    0x10 => |emu| {
+        // PSHA
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.a);
+    },
+    0x11 => |emu| {
+        // PSHX
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.x);
+    },
+    0x12 => |emu| {
+        // PSHY
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.y);
+    },
+    0x13 => |emu| {
+        // PSHC
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.cc.data);
+    },
+    0x14 => |emu| {
+        // PULA
+        let val = emu.read(emu.reg.sp);
+        emu.reg.a.set(val);
+        emu.reg.sp.inc();
+    },
+    0x15 => |emu| {
+        // PULX
+        let val = emu.read(emu.reg.sp);
+        emu.reg.x.set(val);
+        emu.reg.sp.inc();
+    },
+    0x16 => |emu| {
+        // PULY
+        let val = emu.read(emu.reg.sp);
+        emu.reg.y.set(val);
+        emu.reg.sp.inc();
+    },
+    0x17 => |emu| {
+        // PULC
+        let val = emu.read(emu.reg.sp);
+        emu.reg.cc.overwrite(val);
+        emu.reg.sp.inc();
+    },
+    0x18 => |emu| {
+        // TFR A,CC
+        emu.reg.cc.overwrite(emu.reg.a.get());
+    },
+    0x19 => |emu| {
+        // TFR CC,A
+        emu.reg.a.set(emu.reg.cc.data);
+    },
+    0x1a => |emu| {
+        // TFR X,Y
+        emu.reg.y.set(emu.reg.x.get());
+    },
+    0x1b => |emu| {
+        // TFR Y,X
+        emu.reg.x.set(emu.reg.y.get());
+    },
+    0x1c => |emu| {
+        // TFR X,SP
+        emu.reg.sp.set(emu.reg.x.get());
+    },
+    0x1d => |emu| {
+        // TFR SP,X
+        emu.reg.x.set(emu.reg.sp.get());
+    },
+    0x1e => |emu| {
+        // TFR Y,SP
+        emu.reg.sp.set(emu.reg.y.get());
+    },
+    0x1f => |emu| {
+        // TFR SP,Y
+        emu.reg.y.set(emu.reg.sp.get());
+    },
+    0x20 => |emu| {
+        // BSR Adr
+        let offset = emu.fetch_operand();
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.pc);
+        let (new_pc, _, _) = emu.reg.pc + offset;
+        emu.reg.pc.set(new_pc);
+    },
+    0x21 => |emu| {
+        // BRA Adr
+        let offset = emu.fetch_operand();
+        let (new_pc, _, _) = emu.reg.pc + offset;
+        emu.reg.pc.set(new_pc);
+    },
+    0x22 => |emu| {
+        // BMI Adr
+        let offset = emu.fetch_operand();
+        if emu.reg.cc.get(CCFlag::N) {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x23 => |emu| {
+        // BPL Adr
+        let offset = emu.fetch_operand();
+        if !emu.reg.cc.get(CCFlag::N) {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x24 => |emu| {
+        // BEQ Adr
+        let z = emu.reg.cc.get(CCFlag::Z);
+        let offset = emu.fetch_operand();
+        if z {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x25 => |emu| {
+        // BNE Adr
+        let offset = emu.fetch_operand();
+        if !emu.reg.cc.get(CCFlag::Z) {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x26 => |emu| {
+        // BVS Adr
+        let offset = emu.fetch_operand();
+        if emu.reg.cc.get(CCFlag::V) {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x27 => |emu| {
+        // BVC Adr
+        let offset = emu.fetch_operand();
+        if !emu.reg.cc.get(CCFlag::V) {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x28 => |emu| {
+        // BCS Adr
+        let c = emu.reg.cc.get(CCFlag::C);
+        let offset = emu.fetch_operand();
+        if c {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x29 => |emu| {
+        // BCC Adr
+        let c = emu.reg.cc.get(CCFlag::C);
+        let offset = emu.fetch_operand();
+        if !c {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x2a => |emu| {
+        // BHI Adr
+        let c = emu.reg.cc.get(CCFlag::C);
+        let z = emu.reg.cc.get(CCFlag::Z);
+        let offset = emu.fetch_operand();
+        if !(c || z) {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x2b => |emu| {
+        // BLS Adr
+        let c = emu.reg.cc.get(CCFlag::C);
+        let z = emu.reg.cc.get(CCFlag::Z);
+        let offset = emu.fetch_operand();
+        if c || z {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x2c => |emu| {
+        // BGT Adr
+        let n = emu.reg.cc.get(CCFlag::N);
+        let v = emu.reg.cc.get(CCFlag::V);
+        let z = emu.reg.cc.get(CCFlag::Z);
+        let offset = emu.fetch_operand();
+        if !(n != v || z) {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x2d => |emu| {
+        // BGE Adr
+        let offset = emu.fetch_operand();
+        if emu.reg.cc.get(CCFlag::N) == emu.reg.cc.get(CCFlag::V) {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x2e => |emu| {
+        // BLE Adr
+        let n = emu.reg.cc.get(CCFlag::N);
+        let v = emu.reg.cc.get(CCFlag::V);
+        let z = emu.reg.cc.get(CCFlag::Z);
+        let offset = emu.fetch_operand();
+        if n != v || z {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x2f => |emu| {
+        // BLT Adr
+        let offset = emu.fetch_operand();
+        if emu.reg.cc.get(CCFlag::N) != emu.reg.cc.get(CCFlag::V) {
+            let (new_pc, _, _) = emu.reg.pc + offset;
+            emu.reg.pc.set(new_pc);
+        }
+    },
+    0x30 => |emu| {
+        // STX Adr
+        let adr = emu.fetch_operand();
+        emu.write(adr, emu.reg.x.get());
+    },
+    0x31 => |emu| {
+        // STY Adr
+        let adr = emu.fetch_operand();
+        emu.write(adr, emu.reg.y.get());
+    },
+    0x32 => |emu| {
+        // STSP Adr
+        let adr = emu.fetch_operand();
+        emu.write(adr, emu.reg.sp.get());
+    },
+    0x33 => |emu| {
+        // JMP Adr
+        let adr = emu.fetch_operand();
+        emu.reg.pc.set(adr);
+    },
+    0x34 => |emu| {
+        // JSR Adr
+        let adr = emu.fetch_operand();
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.pc);
+        emu.reg.pc.set(adr);
+    },
+    0x35 => |emu| {
+        // CLR Adr
+        let adr = emu.fetch_operand();
+        emu.write(adr, 0);
+        emu.set_clr_flags();
+    },
+    0x36 => |emu| {
+        // NEG Adr
+        let adr = emu.fetch_operand();
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(0, val);
+        emu.write(adr, new_val);
+        emu.set_neg_flags(new_val, val, v);
+    },
+    0x37 => |emu| {
+        // INC Adr
+        let adr = emu.fetch_operand();
+        let val = emu.read(adr);
+        let (new_val, _c, v) = add_c(val, 1, false);
+        emu.write(adr, new_val);
+        emu.set_inc_flags(new_val, v);
+    },
+    0x38 => |emu| {
+        // DEC Adr
+        let adr = emu.fetch_operand();
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(val, 1);
+        emu.write(adr, new_val);
+        emu.set_dec_flags(new_val, v);
+    },
+    0x39 => |emu| {
+        // TST Adr
+        let adr = emu.fetch_operand();
+        let val = emu.read(adr);
+        emu.set_tst_flags(val);
+    },
+    0x3a => |emu| {
+        // COM Adr
+        let adr = emu.fetch_operand();
+        let new_val = !emu.read(adr);
+        emu.write(adr, new_val);
+        emu.set_com_flags(new_val);
+    },
+    0x3b => |emu| {
+        // ASL Adr / LSL Adr
+        let adr = emu.fetch_operand();
+        let (new_val, c, v) = shl(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asl_flags(new_val, c, v);
+    },
+    0x3c => |emu| {
+        // LSR Adr
+        let adr = emu.fetch_operand();
+        let (new_val, c, v) = shr(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_lsr_flags(new_val, c, v);
+    },
+    0x3d => |emu| {
+        // ROL Adr
+        let adr = emu.fetch_operand();
+        let (new_val, c) = rotate_left(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_rol_flags(new_val, c);
+    },
+    0x3e => |emu| {
+        // ROR Adr
+        let adr = emu.fetch_operand();
+        let (new_val, c) = rotate_right(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_ror_flags(new_val, c);
+    },
+    0x3f => |emu| {
+        // ASR Adr
+        let adr = emu.fetch_operand();
+        let (new_val, c) = shr_signed(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asr_flags(new_val, c);
+    },
+    0x40 => |emu| {
+        // STX n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        emu.write(adr, emu.reg.x.get());
+    },
+    0x41 => |emu| {
+        // STY n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        emu.write(adr, emu.reg.y.get());
+    },
+    0x42 => |emu| {
+        // STSP n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        emu.write(adr, emu.reg.sp.get());
+    },
+    0x43 => |emu| {
+        // RTS
+        let return_addr = emu.read(emu.reg.sp);
+        emu.reg.pc.set(return_addr);
+        emu.reg.sp.inc();
+    },
+    0x44 => |emu| {
+        // RTI
+        let data = emu.read(emu.reg.sp);
+        emu.reg.cc.overwrite(data);
+        emu.reg.sp.inc();
+        let data = emu.read(emu.reg.sp);
+        emu.reg.a.set(data);
+        emu.reg.sp.inc();
+        let data = emu.read(emu.reg.sp);
+        emu.reg.x.set(data);
+        emu.reg.sp.inc();
+        let data = emu.read(emu.reg.sp);
+        emu.reg.y.set(data);
+        emu.reg.sp.inc();
+        let data = emu.read(emu.reg.sp);
+        emu.reg.pc.set(data);
+        emu.reg.sp.inc();
+    },
+    0x45 => |emu| {
+        // CLR n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        emu.write(adr, 0);
+        emu.set_clr_flags();
+    },
+    0x46 => |emu| {
+        // NEG n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(0, val);
+        emu.write(adr, new_val);
+        emu.set_neg_flags(new_val, val, v);
+    },
+    0x47 => |emu| {
+        // INC n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = add_c(val, 1, false);
+        emu.write(adr, new_val);
+        emu.set_inc_flags(new_val, v);
+    },
+    0x48 => |emu| {
+        // DEC n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(val, 1);
+        emu.write(adr, new_val);
+        emu.set_dec_flags(new_val, v);
+    },
+    0x49 => |emu| {
+        // TST n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let val = emu.read(adr);
+        emu.set_tst_flags(val);
+    },
+    0x4a => |emu| {
+        // COM n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let new_val = !emu.read(adr);
+        emu.write(adr, new_val);
+        emu.set_com_flags(new_val);
+    },
+    0x4b => |emu| {
+        // ASL n,SP / LSL n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let (new_val, c, v) = shl(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asl_flags(new_val, c, v);
+    },
+    0x4c => |emu| {
+        // LSR n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let (new_val, c, v) = shr(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_lsr_flags(new_val, c, v);
+    },
+    0x4d => |emu| {
+        // ROL n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let (new_val, c) = rotate_left(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_rol_flags(new_val, c);
+    },
+    0x4e => |emu| {
+        // ROR n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let (new_val, c) = rotate_right(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_ror_flags(new_val, c);
+    },
+    0x4f => |emu| {
+        // ASR n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let (new_val, c) = shr_signed(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asr_flags(new_val, c);
+    },
+    0x50 => |emu| {
+        // STX n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        emu.write(adr, emu.reg.x.get());
+    },
+    0x51 => |emu| {
+        // STY n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        emu.write(adr, emu.reg.y.get());
+    },
+    0x52 => |emu| {
+        // STSP n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        emu.write(adr, emu.reg.sp.get());
+    },
+    0x53 => |emu| {
+        // JMP n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        emu.reg.pc.set(adr);
+    },
+    0x54 => |emu| {
+        // JSR n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.pc);
+        emu.reg.pc.set(adr);
+    },
+    0x55 => |emu| {
+        // CLR n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        emu.write(adr, 0);
+        emu.set_clr_flags();
+    },
+    0x56 => |emu| {
+        // NEG n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(0, val);
+        emu.write(adr, new_val);
+        emu.set_neg_flags(new_val, val, v);
+    },
+    0x57 => |emu| {
+        // INC n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = add_c(val, 1, false);
+        emu.write(adr, new_val);
+        emu.set_inc_flags(new_val, v);
+    },
+    0x58 => |emu| {
+        // DEC n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(val, 1);
+        emu.write(adr, new_val);
+        emu.set_dec_flags(new_val, v);
+    },
+    0x59 => |emu| {
+        // TST n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let val = emu.read(adr);
+        emu.set_tst_flags(val);
+    },
+    0x5a => |emu| {
+        // COM n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let new_val = !emu.read(adr);
+        emu.write(adr, new_val);
+        emu.set_com_flags(new_val);
+    },
+    0x5b => |emu| {
+        // ASL n,X / LSL n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let (new_val, c, v) = shl(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asl_flags(new_val, c, v);
+    },
+    0x5c => |emu| {
+        // LSR n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let (new_val, c, v) = shr(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_lsr_flags(new_val, c, v);
+    },
+    0x5d => |emu| {
+        // ROL n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let (new_val, c) = rotate_left(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_rol_flags(new_val, c);
+    },
+    0x5e => |emu| {
+        // ROR n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let (new_val, c) = rotate_right(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_ror_flags(new_val, c);
+    },
+    0x5f => |emu| {
+        // ASR n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let (new_val, c) = shr_signed(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asr_flags(new_val, c);
+    },
+    0x60 => |emu| {
+        // STX A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        emu.write(adr, emu.reg.x.get());
+    },
+    0x61 => |emu| {
+        // TODO: FLISP-hanbook said OP-code 60, but I assume it should be 61.
+        // STY A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        emu.write(adr, emu.reg.y.get());
+    },
+    0x62 => |emu| {
+        // STSP A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        emu.write(adr, emu.reg.sp.get());
+    },
+    0x63 => |emu| {
+        // JMP A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        emu.reg.pc.set(adr);
+    },
+    0x64 => |emu| {
+        // JSR A,X
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.pc);
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        emu.reg.pc.set(adr);
+    },
+    0x67 => |emu| {
+        // INC A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = add_c(val, 1, false);
+        emu.write(adr, new_val);
+        emu.set_inc_flags(new_val, v);
+    },
+    0x65 => |emu| {
+        // CLR A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        emu.write(adr, 0);
+        emu.set_clr_flags();
+    },
+    0x66 => |emu| {
+        // NEG A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(0, val);
+        emu.write(adr, new_val);
+        emu.set_neg_flags(new_val, val, v);
+    },
+    0x68 => |emu| {
+        // DEC A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(val, 1);
+        emu.write(adr, new_val);
+        emu.set_dec_flags(new_val, v);
+    },
+    0x69 => |emu| {
+        // TST A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let val = emu.read(adr);
+        emu.set_tst_flags(val);
+    },
+    0x6a => |emu| {
+        // COM A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let new_val = !emu.read(adr);
+        emu.write(adr, new_val);
+        emu.set_com_flags(new_val);
+    },
+    0x6b => |emu| {
+        // ASL A,X / LSL A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let (new_val, c, v) = shl(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asl_flags(new_val, c, v);
+    },
+    0x6c => |emu| {
+        // LSR A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let (new_val, c, v) = shr(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_lsr_flags(new_val, c, v);
+    },
+    0x6d => |emu| {
+        // ROL A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let (new_val, c) = rotate_left(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_rol_flags(new_val, c);
+    },
+    0x6e => |emu| {
+        // ROR A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let (new_val, c) = rotate_right(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_ror_flags(new_val, c);
+    },
+    0x6f => |emu| {
+        // ASR A,X
+        let (adr, _, _) = emu.reg.a + emu.reg.x;
+        let (new_val, c) = shr_signed(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asr_flags(new_val, c);
+    },
+    0x70 => |emu| {
+        // STX n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        emu.write(adr, emu.reg.x.get());
+    },
+    0x71 => |emu| {
+        // STY n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        emu.write(adr, emu.reg.y.get());
+    },
+    0x72 => |emu| {
+        // STSP n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        emu.write(adr, emu.reg.sp.get());
+    },
+    0x73 => |emu| {
+        // JMP n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        emu.reg.pc.set(adr);
+    },
+    0x74 => |emu| {
+        // JSR n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.pc);
+        emu.reg.pc.set(adr);
+    },
+    0x75 => |emu| {
+        // CLR n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        emu.write(adr, 0);
+        emu.set_clr_flags();
+    },
+    0x76 => |emu| {
+        // NEG n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(0, val);
+        emu.write(adr, new_val);
+        emu.set_neg_flags(new_val, val, v);
+    },
+    0x77 => |emu| {
+        // INC n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = add_c(val, 1, false);
+        emu.write(adr, new_val);
+        emu.set_inc_flags(new_val, v);
+    },
+    0x78 => |emu| {
+        // DEC n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(val, 1);
+        emu.write(adr, new_val);
+        emu.set_dec_flags(new_val, v);
+    },
+    0x79 => |emu| {
+        // TST n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let val = emu.read(adr);
+        emu.set_tst_flags(val);
+    },
+    0x7a => |emu| {
+        // COM n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let new_val = !emu.read(adr);
+        emu.write(adr, new_val);
+        emu.set_com_flags(new_val);
+    },
+    0x7b => |emu| {
+        // ASL n,Y / LSL n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let (new_val, c, v) = shl(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asl_flags(new_val, c, v);
+    },
+    0x7c => |emu| {
+        // LSR n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let (new_val, c, v) = shr(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_lsr_flags(new_val, c, v);
+    },
+    0x7d => |emu| {
+        // ROL n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let (new_val, c) = rotate_left(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_rol_flags(new_val, c);
+    },
+    0x7e => |emu| {
+        // ROR n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let (new_val, c) = rotate_right(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_ror_flags(new_val, c);
+    },
+    0x7f => |emu| {
+        // ASR n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let (new_val, c) = shr_signed(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asr_flags(new_val, c);
+    },
+    0x80 => |emu| {
+        // STX A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        emu.write(adr, emu.reg.x.get());
+    },
+    0x81 => |emu| {
+        // STY A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        emu.write(adr, emu.reg.y.get());
+    },
+    0x82 => |emu| {
+        // STSP A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        emu.write(adr, emu.reg.sp.get());
+    },
+    0x83 => |emu| {
+        // JMP A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        emu.reg.pc.set(adr);
+    },
+    0x84 => |emu| {
+        // JSR A,Y
+        emu.reg.sp.dec();
+        emu.write(emu.reg.sp.get(), emu.reg.pc);
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        emu.reg.pc.set(adr);
+    },
+    0x85 => |emu| {
+        // CLR A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        emu.write(adr, 0);
+        emu.set_clr_flags();
+    },
+    0x86 => |emu| {
+        // NEG A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(0, val);
+        emu.write(adr, new_val);
+        emu.set_neg_flags(new_val, val, v);
+    },
+    0x87 => |emu| {
+        // INC A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = add_c(val, 1, false);
+        emu.write(adr, new_val);
+        emu.set_inc_flags(new_val, v);
+    },
+    0x88 => |emu| {
+        // DEC A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let val = emu.read(adr);
+        let (new_val, _c, v) = sub(val, 1);
+        emu.write(adr, new_val);
+        emu.set_dec_flags(new_val, v);
+    },
+    0x89 => |emu| {
+        // TST A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let val = emu.read(adr);
+        emu.set_tst_flags(val);
+    },
+    0x8a => |emu| {
+        // COM A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let new_val = !emu.read(adr);
+        emu.write(adr, new_val);
+        emu.set_com_flags(new_val);
+    },
+    0x8b => |emu| {
+        // ASL A,Y / LSL A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let (new_val, c, v) = shl(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asl_flags(new_val, c, v);
+    },
+    0x8c => |emu| {
+        // LSR A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let (new_val, c, v) = shr(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_lsr_flags(new_val, c, v);
+    },
+    0x8d => |emu| {
+        // ROL A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let (new_val, c) = rotate_left(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_rol_flags(new_val, c);
+    },
+    0x8e => |emu| {
+        // ROR A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let (new_val, c) = rotate_right(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_ror_flags(new_val, c);
+    },
+    0x8f => |emu| {
+        // ASR A,Y
+        let (adr, _, _) = emu.reg.a + emu.reg.y;
+        let (new_val, c) = shr_signed(emu.read(adr));
+        emu.write(adr, new_val);
+        emu.set_asr_flags(new_val, c);
+    },
+    0x90 => |emu| {
+        // LDX #Data
+        let data = emu.fetch_operand();
+        emu.reg.x.set(data);
+        emu.set_ldx_flags();
+    },
+    0x91 => |emu| {
+        // LDY #Data
+        let data = emu.fetch_operand();
+        emu.reg.y.set(data);
+        emu.set_ldy_flags();
+    },
+    0x92 => |emu| {
+        // LDSP #Data
+        let data = emu.fetch_operand();
+        emu.reg.sp.set(data);
+        emu.set_ldsp_flags();
+    },
+    0x93 => |emu| {
+        // SBCA #Data
+        let data = emu.fetch_operand();
+        let (diff, c, v) = sub_c(emu.reg.a, data, emu.reg.cc.get(CCFlag::C));
+        emu.reg.a.set(diff);
+        emu.set_sbc_flags(diff, c, v);
+    },
+    0x94 => |emu| {
+        // SUBA #Data
+        let data = emu.fetch_operand();
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.reg.a.set(diff);
+        emu.set_suba_flags(diff, c, v);
+    },
+    0x95 => |emu| {
+        // ADCA #Data
+        let data = emu.fetch_operand();
+        let (sum, c, v) = add(emu.reg.a, data);
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0x96 => |emu| {
+        // ADDA #Data
+        let data = emu.fetch_operand();
+        let (sum, c, v) = emu.reg.a + data;
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0x97 => |emu| {
+        // CMPA #Data
+        let data = emu.fetch_operand();
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0x98 => |emu| {
+        // BITA #Data
+        let data = emu.fetch_operand();
+        let result = emu.reg.a & data;
+        emu.set_bita_flags(result);
+    },
+    0x99 => |emu| {
+        // ANDA #Data
+        let data = emu.fetch_operand();
+        let result = emu.reg.a & data;
+        emu.reg.a.set(result);
+        emu.set_anda_flags();
+    },
+    0x9a => |emu| {
+        // ORA #Data
+        let data = emu.fetch_operand();
+        let result = emu.reg.a.get() | data;
+        emu.reg.a.set(result);
+        emu.set_ora_flags(result);
+    },
+    0x9b => |emu| {
+        // EORA #Data
+        let data = emu.fetch_operand();
+        let result = emu.reg.a.get() ^ data;
+        emu.reg.a.set(result);
+        emu.set_eora_flags(result);
+    },
+    0x9c => |emu| {
+        // CMPX #Data
+        let data = emu.fetch_operand();
+        let (diff, c, v) = sub(emu.reg.x, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0x9d => |emu| {
+        // CMPY #Data
+        let data = emu.fetch_operand();
+        let (diff, c, v) = sub(emu.reg.y, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0x9e => |emu| {
+        // CMPSP #Data
+        let data = emu.fetch_operand();
+        let (diff, c, v) = sub(emu.reg.sp, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0x9f => |emu| {
+        // EXG A,CC
+        let temp = emu.reg.a.get();
+        emu.reg.a.set(emu.reg.cc.data);
+        emu.reg.cc.data = temp & 0b1111; // Keep only lower 4 bits (N,Z,V,C)
+    },
+    0xa0 => |emu| {
+        // LDX Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        emu.reg.x.set(data);
+        emu.set_ldx_flags();
+    },
+    0xa1 => |emu| {
+        // LDY Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        emu.reg.y.set(data);
+        emu.set_ldy_flags();
+    },
+    0xa2 => |emu| {
+        // LDSP Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        emu.reg.sp.set(data);
+        emu.set_ldsp_flags();
+    },
+    0xa3 => |emu| {
+        // SBCA Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let (diff, c, v) = sub_c(emu.reg.a, data, emu.reg.cc.get(CCFlag::C));
+        emu.reg.a.set(diff);
+        emu.set_sbc_flags(diff, c, v);
+    },
+    0xa4 => |emu| {
+        // SUBA Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.reg.a.set(diff);
+        emu.set_suba_flags(diff, c, v);
+    },
+    0xa5 => |emu| {
+        // ADCA Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let (sum, c, v) = add_c(emu.reg.a, data, emu.reg.cc.get(CCFlag::C));
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0xa6 => |emu| {
+        // ADDA Adr
+        let adr = emu.fetch_operand();
+        let (sum, c, v) = emu.read(adr) + emu.reg.a;
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0xa7 => |emu| {
+        // CMPA Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0xa8 => |emu| {
+        // BITA Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let result = emu.reg.a & data;
+        emu.set_bita_flags(result);
+    },
+    0xa9 => |emu| {
+        // ANDA Adr
+        let adr = emu.fetch_operand();
+        let result = emu.reg.a & emu.read(adr);
+        emu.reg.a.set(result);
+        emu.set_anda_flags();
+    },
+    0xaa => |emu| {
+        // ORA Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let result = emu.reg.a.get() | data;
+        emu.reg.a.set(result);
+        emu.set_ora_flags(result);
+    },
+    0xab => |emu| {
+        // EORA Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let result = emu.reg.a.get() ^ data;
+        emu.reg.a.set(result);
+        emu.set_eora_flags(result);
+    },
+    0xac => |emu| {
+        // CMPX Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.x, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0xad => |emu| {
+        // CMPY Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.y, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0xae => |emu| {
+        // CMPSP Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.sp, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0xaf => |emu| {
+        // EXG X,Y
+        let temp = emu.reg.x.get();
+        emu.reg.x.set(emu.reg.y.get());
+        emu.reg.y.set(temp);
+    },
+    0xb0 => |emu| {
+        // LDX n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        emu.reg.x.set(data);
+        emu.set_ldx_flags();
+    },
+    0xb1 => |emu| {
+        // LDY n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        emu.reg.y.set(data);
+        emu.set_ldy_flags();
+    },
+    0xb2 => |emu| {
+        // LDSP n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        emu.reg.sp.set(data);
+        emu.set_ldsp_flags();
+    },
+    0xb3 => |emu| {
+        // SBCA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub_c(emu.reg.a, data, emu.reg.cc.get(CCFlag::C));
+        emu.reg.a.set(diff);
+        emu.set_sbc_flags(diff, c, v);
+    },
+    0xb4 => |emu| {
+        // SUBA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.reg.a.set(diff);
+        emu.set_suba_flags(diff, c, v);
+    },
+    0xb5 => |emu| {
+        // ADCA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        let (sum, c, v) = add_c(emu.reg.a, data, emu.reg.cc.get(CCFlag::C));
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0xb6 => |emu| {
+        // ADDA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let (sum, c, v) = emu.reg.a + emu.read(adr);
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0xb7 => |emu| {
+        // CMPA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0xb8 => |emu| {
+        // BITA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        emu.set_bita_flags(emu.reg.a & data);
+    },
+    0xb9 => |emu| {
+        // ANDA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        let result = emu.reg.a & data;
+        emu.reg.a.set(result);
+        emu.set_anda_flags();
+    },
+    0xba => |emu| {
+        // ORA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        let result = emu.reg.a.get() | data;
+        emu.reg.a.set(result);
+        emu.set_ora_flags(result);
+    },
+    0xbb => |emu| {
+        // EORA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        let result = emu.reg.a.get() ^ data;
+        emu.reg.a.set(result);
+        emu.set_eora_flags(result);
+    },
+    0xbc => |emu| {
+        // CMPX n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.x, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0xbd => |emu| {
+        // CMPY n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.y, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0xbe => |emu| {
+        // LEASP n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        emu.reg.sp.set(adr);
+    },
+    0xbf => |emu| {
+        // EXG X,SP
+        let temp = emu.reg.x.get();
+        emu.reg.x.set(emu.reg.sp.get());
+        emu.reg.sp.set(temp);
+    },
+    0xc0 => |emu| {
+        // LDX n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        emu.reg.x.set(data);
+        emu.set_ldx_flags();
+    },
+    0xc1 => |emu| {
+        // LDY n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        emu.reg.y.set(data);
+        emu.set_ldy_flags();
+    },
+    0xc2 => |emu| {
+        // LDSP n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        emu.reg.sp.set(data);
+        emu.set_ldsp_flags();
+    },
+    0xc3 => |emu| {
+        // SBCA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub_c(emu.reg.a, data, emu.reg.cc.get(CCFlag::C));
+        emu.reg.a.set(diff);
+        emu.set_sbc_flags(diff, c, v);
+    },
+    0xc4 => |emu| {
+        // SUBA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.reg.a.set(diff);
+        emu.set_suba_flags(diff, c, v);
+    },
+    0xc5 => |emu| {
+        // ADCA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        let (sum, c, v) = add_c(emu.reg.a, data, emu.reg.cc.get(CCFlag::C));
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0xc6 => |emu| {
+        // ADDA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let (sum, c, v) = emu.reg.a + emu.read(adr);
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0xc7 => |emu| {
+        // CMPA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0xc8 => |emu| {
+        // BITA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        let result = emu.reg.a & data;
+        emu.set_bita_flags(result);
+    },
+    0xc9 => |emu| {
+        // ANDA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        let result = emu.reg.a & data;
+        emu.reg.a.set(result);
+        emu.set_anda_flags();
+    },
+    0xca => |emu| {
+        // ORA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        let result = emu.reg.a.get() | data;
+        emu.reg.a.set(result);
+        emu.set_ora_flags(result);
+    },
+    0xcb => |emu| {
+        // EORA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        let result = emu.reg.a.get() ^ data;
+        emu.reg.a.set(result);
+        emu.set_eora_flags(result);
+    },
+    0xcc => |emu| {
+        // LEAX n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        emu.reg.x.set(adr);
+    },
+    0xcd => |emu| {
+        // LEAY n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        emu.reg.y.set(adr);
+    },
+    0xce => |emu| {
+        // LEASP n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        emu.reg.sp.set(adr);
+    },
+    0xcf => |emu| {
+        // EXG Y,SP
+        let temp = emu.reg.y.get();
+        emu.reg.y.set(emu.reg.sp.get());
+        emu.reg.sp.set(temp);
+    },
+    0xd0 => |emu| {
+        // LDX n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        emu.reg.x.set(data);
+        emu.set_ldx_flags();
+    },
+    0xd1 => |emu| {
+        // LDY n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        emu.reg.y.set(data);
+        emu.set_ldy_flags();
+    },
+    0xd2 => |emu| {
+        // LDSP n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        emu.reg.sp.set(data);
+        emu.set_ldsp_flags();
+    },
+    0xd3 => |emu| {
+        // SBCA n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub_c(emu.reg.a, data, emu.reg.cc.get(CCFlag::C));
+        emu.reg.a.set(diff);
+        emu.set_sbc_flags(diff, c, v);
+    },
+    0xd4 => |emu| {
+        // SUBA n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.reg.a.set(diff);
+        emu.set_suba_flags(diff, c, v);
+    },
+    0xd5 => |emu| {
+        // ADCA n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        let (sum, c, v) = add_c(emu.reg.a, data, emu.reg.cc.get(CCFlag::C));
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0xd6 => |emu| {
+        // ADDA n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let (sum, c, v) = emu.reg.a + emu.read(adr);
+        emu.reg.a.set(sum);
+        emu.set_add_flags(sum, c, v);
+    },
+    0xd7 => |emu| {
+        // CMPA n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        let (diff, c, v) = sub(emu.reg.a, data);
+        emu.set_cmp_flags(diff, c, v);
+    },
+    0xd8 => |emu| {
+        // BITA n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        let result = emu.reg.a & data;
+        emu.set_bita_flags(result);
+    },
+    0xd9 => |emu| {
+        // ANDA n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        let result = emu.reg.a & data;
+        emu.reg.a.set(result);
+        emu.set_anda_flags();
+    },
+    0xda => |emu| {
+        // ORA n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        let result = emu.reg.a.get() | data;
+        emu.reg.a.set(result);
+        emu.set_ora_flags(result);
+    },
+    0xdb => |emu| {
+        // EORA n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        let data = emu.read(adr);
+        let result = emu.reg.a.get() ^ data;
+        emu.reg.a.set(result);
+        emu.set_eora_flags(result);
+    },
+    0xdc => |emu| {
+        // LEAX n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        emu.reg.x.set(adr);
+    },
+    0xdd => |emu| {
+        // LEAY n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        emu.reg.y.set(adr);
+    },
+    0xde => |emu| {
+        // LEASP n,Y
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.y;
+        emu.reg.sp.set(adr);
+    },
+    0xe1 => |emu| {
+        // STA Adr
+        let adr = emu.fetch_operand();
+        emu.write(adr, emu.reg.a);
+    },
+    0xe2 => |emu| {
+        // STA n,SP
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.sp;
+        emu.write(adr, emu.reg.a);
+    },
+    0xe3 => |emu| {
+        // STA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        emu.write(adr, emu.reg.a);
+    },
+    0xe4 => |emu| {
+        // STA A,X
+        let (sum, _, _) = emu.reg.a + emu.reg.x;
+        emu.write(sum, emu.reg.a);
+    },
+    0xe5 => |emu| {
+        // STA ,X+
+        emu.write(emu.reg.x.get(), emu.reg.a);
+        emu.reg.x.inc();
+    },
+    0xe6 => |emu| {
+        // STA ,X-
+        emu.write(emu.reg.x.get(), emu.reg.a);
+        emu.reg.x.dec();
+    },
+    0xe7 => |emu| {
+        // STA ,+X
+        emu.reg.x.inc();
+        emu.write(emu.reg.x.get(), emu.reg.a);
+    },
+    0xe8 => |emu| {
+        // STA ,-X
+        emu.reg.x.dec();
+        emu.write(emu.reg.x.get(), emu.reg.a);
+    },
+    0xe9 => |emu| {
+        // STA n,Y
+        let n = emu.fetch_operand();
+        let (sum, _, _) = n + emu.reg.y;
+        emu.write(sum, emu.reg.a);
+    },
+    0xea => |emu| {
+        // STA A,Y
+        let (sum, _, _) = emu.reg.a + emu.reg.y;
+        emu.write(sum, emu.reg.a);
+    },
+    0xeb => |emu| {
+        // STA ,Y+
+        emu.write(emu.reg.y.get(), emu.reg.a);
+        emu.reg.y.inc();
+    },
+    0xec => |emu| {
+        // STA ,Y-
+        emu.write(emu.reg.y.get(), emu.reg.a);
+        emu.reg.y.dec();
+    },
+    0xed => |emu| {
+        // STA ,+Y
+        emu.reg.y.inc();
+        emu.write(emu.reg.y.get(), emu.reg.a);
+    },
+    0xee => |emu| {
+        // STA ,-Y
+        emu.reg.y.dec();
+        emu.write(emu.reg.y.get(), emu.reg.a);
+    },
+    0xf0 => |emu| {
+        // LDA #Data
+        let data = emu.fetch_operand();
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xf1 => |emu| {
+        // LDA Adr
+        let adr = emu.fetch_operand();
+        let data = emu.read(adr);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xf2 => |emu| {
+        // LDA n, SP
+        let n = emu.fetch_operand();
+        let (sum, _, _) = n + emu.reg.sp;
+        let data = emu.read(sum);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xf3 => |emu| {
+        // LDA n,X
+        let n = emu.fetch_operand();
+        let (adr, _, _) = n + emu.reg.x;
+        let data = emu.read(adr);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xf4 => |emu| {
+        // LDA A,X
+        let (sum, _, _) = emu.reg.a + emu.reg.x;
+        let data = emu.read(sum);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xf5 => |emu| {
+        // LDA ,X+
+        let data = emu.read(emu.reg.x);
+        emu.reg.a.set(data);
+        emu.reg.x.inc();
+        emu.set_lda_flags();
+    },
+    0xf6 => |emu| {
+        // LDA ,X-
+        let data = emu.read(emu.reg.x);
+        emu.reg.a.set(data);
+        emu.reg.x.dec();
+        emu.set_lda_flags();
+    },
+    0xf7 => |emu| {
+        // LDA ,+X
+        emu.reg.x.inc();
+        let data = emu.read(emu.reg.x);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xf8 => |emu| {
+        // LDA ,-X
+        emu.reg.x.dec();
+        let data = emu.read(emu.reg.x);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xf9 => |emu| {
+        // LDA n,Y
+        let n = emu.fetch_operand();
+        let (sum, _, _) = n + emu.reg.y;
+        let data = emu.read(sum);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xfa => |emu| {
+        // LDA A,Y
+        let (sum, _, _) = emu.reg.a + emu.reg.y;
+        let data = emu.read(sum);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xfb => |emu| {
+        // LDA ,Y+
+        let data = emu.read(emu.reg.y);
+        emu.reg.a.set(data);
+        emu.reg.y.inc();
+        emu.set_lda_flags();
+    },
+    0xfc => |emu| {
+        // LDA ,Y-
+        let data = emu.read(emu.reg.y);
+        emu.reg.a.set(data);
+        emu.reg.y.dec();
+        emu.set_lda_flags();
+    },
+    0xfd => |emu| {
+        // LDA ,+Y
+        emu.reg.y.inc();
+        let data = emu.read(emu.reg.y);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+    0xfe => |emu| {
+        // LDA ,-Y
+        emu.reg.y.dec();
+        let data = emu.read(emu.reg.y);
+        emu.reg.a.set(data);
+        emu.set_lda_flags();
+    },
+};
@@ -0,0 +1,212 @@
+use std::{collections::VecDeque, mem::size_of};
+
+use crate::{Emulator, QState, RegisterStore, access::AccessKind, call_stack::CallFrame};
+
+/// Default history budget, enough for roughly twenty thousand instructions
+pub const DEFAULT_HISTORY_BUDGET: usize = 1 << 20;
+
+/// State needed to undo one `step`
+#[derive(Clone)]
+struct HistoryEntry {
+    reg: RegisterStore,
+    q_state: QState,
+    clk_count: u32,
+    /// (address, previous value) for every write, in execution order
+    writes: Vec<(u8, u8)>,
+    /// Addresses the step wrote for the first time since the last reset
+    first_writes: Vec<u8>,
+    lowest_sp: Option<u8>,
+    /// Length of the uninitialized read log before the step
+    uninitialized_reads: usize,
+    call_depth: usize,
+    /// Frame popped by an RTS
+    returned: Option<CallFrame>,
+}
+
+impl HistoryEntry {
+    fn size(&self) -> usize {
+        size_of::<Self>()
+            + self.writes.capacity() * size_of::<(u8, u8)>()
+            + self.first_writes.capacity()
+    }
+}
+
+/// Bounded record of recent steps. The oldest entries are dropped once the estimated
+/// memory use exceeds the budget.
+#[derive(Clone)]
+pub(crate) struct History {
+    entries: VecDeque<HistoryEntry>,
+    used: usize,
+    budget: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            used: 0,
+            budget: DEFAULT_HISTORY_BUDGET,
+        }
+    }
+}
+
+impl History {
+    fn push(&mut self, entry: HistoryEntry) {
+        if self.budget == 0 {
+            return;
+        }
+        self.used += entry.size();
+        self.entries.push_back(entry);
+        self.trim();
+    }
+
+    fn pop(&mut self) -> Option<HistoryEntry> {
+        let entry = self.entries.pop_back()?;
+        self.used -= entry.size();
+        Some(entry)
+    }
+
+    fn trim(&mut self) {
+        while self.used > self.budget {
+            match self.entries.pop_front() {
+                Some(entry) => self.used -= entry.size(),
+                None => break,
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.used = 0;
+    }
+}
+
+/// Machine state captured before a step, completed with the step's writes afterwards
+pub(crate) struct PendingEntry {
+    reg: RegisterStore,
+    q_state: QState,
+    clk_count: u32,
+    lowest_sp: Option<u8>,
+    uninitialized_reads: usize,
+    call_depth: usize,
+}
+
+impl Emulator {
+    /// Number of steps that can currently be undone
+    pub fn history_len(&self) -> usize {
+        self.history.entries.len()
+    }
+
+    /// Sets the approximate number of bytes the execution history may use.
+    /// A budget of 0 disables recording.
+    pub fn set_history_budget(&mut self, bytes: usize) {
+        self.history.budget = bytes;
+        self.history.trim();
+    }
+
+    pub fn history_budget(&self) -> usize {
+        self.history.budget
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Undoes the most recent step, restoring registers, clock count and RAM, along with
+    /// the runtime-written marks, stack high-water mark and uninitialized read log.
+    /// Device state is not rewound. Part way through a micro-stepped instruction, this
+    /// returns to the start of that instruction.
+    ///
+    /// Returns: `false` if there is no recorded step to undo
+    pub fn step_back(&mut self) -> bool {
+        if self.abort_micro_sequence() {
+            return true;
+        }
+        let Some(entry) = self.history.pop() else {
+            return false;
+        };
+        self.rewind_call_stack(&entry);
+        for (address, previous) in entry.writes.into_iter().rev() {
+            self.bus.poke_ram(address, previous);
+        }
+        for address in entry.first_writes {
+            self.written[address as usize] = false;
+        }
+        self.lowest_sp = entry.lowest_sp;
+        self.uninitialized_reads.truncate(entry.uninitialized_reads);
+        self.reg = entry.reg;
+        self.q_state = entry.q_state;
+        self.clk_count = entry.clk_count;
+        self.fault = None;
+        self.accesses.clear();
+        self.watchpoint_hits.clear();
+        true
+    }
+
+    /// Steps back until PC rests on an enabled breakpoint whose condition holds.
+    /// Hit counts are not changed.
+    ///
+    /// Returns: the breakpoint address, or `None` if the oldest recorded step was reached
+    pub fn reverse_continue(&mut self) -> Option<u8> {
+        while self.step_back() {
+            let pc = self.reg.pc.get();
+            let Some(bp) = self.breakpoints.get(pc) else {
+                continue;
+            };
+            if bp.is_enabled() && bp.condition().is_none_or(|c| c.evaluate(self)) {
+                return Some(pc);
+            }
+        }
+        None
+    }
+
+    pub(crate) fn begin_history_entry(&self) -> PendingEntry {
+        PendingEntry {
+            reg: self.reg,
+            q_state: self.q_state,
+            clk_count: self.clk_count,
+            lowest_sp: self.lowest_sp,
+            uninitialized_reads: self.uninitialized_reads.len(),
+            call_depth: self.call_stack.len(),
+        }
+    }
+
+    /// Records the step. Called before `check_protection` marks its writes.
+    pub(crate) fn commit_history_entry(
+        &mut self,
+        pending: PendingEntry,
+        returned: Option<CallFrame>,
+    ) {
+        let writes: Vec<_> = self
+            .accesses
+            .iter()
+            .filter(|access| access.kind == AccessKind::Write)
+            .map(|access| (access.address, access.previous))
+            .collect();
+        let mut first_writes: Vec<u8> = writes
+            .iter()
+            .map(|(address, _)| *address)
+            .filter(|address| !self.written[*address as usize])
+            .collect();
+        first_writes.sort_unstable();
+        first_writes.dedup();
+        self.history.push(HistoryEntry {
+            reg: pending.reg,
+            q_state: pending.q_state,
+            clk_count: pending.clk_count,
+            writes,
+            first_writes,
+            lowest_sp: pending.lowest_sp,
+            uninitialized_reads: pending.uninitialized_reads,
+            call_depth: pending.call_depth,
+            returned,
+        });
+    }
+
+    fn rewind_call_stack(&mut self, entry: &HistoryEntry) {
+        if let Some(frame) = entry.returned {
+            self.call_stack.push(frame);
+        }
+        self.call_stack.truncate(entry.call_depth);
+    }
+}
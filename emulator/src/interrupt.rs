@@ -0,0 +1,49 @@
+use crate::{CCFlag, Emulator};
+
+/// Address holding the start address of the interrupt handler
+pub const IRQ_VECTOR: u8 = 0xfd;
+
+/// Clock cycles spent entering an interrupt: five pushes, the vector read and setting I
+pub const IRQ_CYCLES: u32 = 7;
+
+impl Emulator {
+    /// Drives the external IRQ input. The line is level triggered and stays asserted
+    /// until released, either here or by the device that raised it.
+    pub fn set_irq_line(&mut self, asserted: bool) {
+        self.irq_line = asserted;
+    }
+
+    pub fn irq_line(&self) -> bool {
+        self.irq_line
+    }
+
+    /// Whether the IRQ input or any attached device is requesting an interrupt
+    pub fn irq_pending(&self) -> bool {
+        self.irq_line || self.bus.irq_asserted()
+    }
+
+    /// Whether the next step will enter the interrupt handler instead of executing
+    /// the instruction at PC
+    pub(crate) fn interrupt_due(&self) -> bool {
+        self.irq_pending() && !self.reg.cc.get(CCFlag::I)
+    }
+
+    /// Pushes PC, Y, X, A and CC so that RTI restores them, masks further interrupts
+    /// and jumps through the interrupt vector. The caller counts `IRQ_CYCLES`.
+    pub(crate) fn enter_interrupt(&mut self) {
+        for value in [
+            self.reg.pc.get(),
+            self.reg.y.get(),
+            self.reg.x.get(),
+            self.reg.a.get(),
+            self.reg.cc.data,
+        ] {
+            self.reg.sp.dec();
+            self.write(self.reg.sp.get(), value);
+        }
+        self.reg.cc.enable(CCFlag::I);
+
+        let handler = self.fetch(IRQ_VECTOR);
+        self.reg.pc.set(handler);
+    }
+}
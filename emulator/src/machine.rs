@@ -0,0 +1,441 @@
+use std::{
+    fmt,
+    ops::{Range, RangeInclusive},
+    path::Path,
+};
+
+use serde::{Deserialize, Deserializer, de};
+use toml::Spanned;
+
+use crate::{
+    Emulator,
+    bus::{BusError, Device},
+    devices::{Console, DigitCountError, Keypad, Leds, MAX_DIGITS, SevenSegment, Switches, Timer},
+    protection::Protection,
+};
+
+/// Name the CLI looks for next to the program when no machine description is given
+pub const MACHINE_FILE: &str = "flisp-machine.toml";
+
+/// Digits of a seven-segment display given no `digits` setting, as on the lab board
+const DEFAULT_DIGITS: usize = 4;
+
+/// Memory map and peripherals of a FLISP board, usually read from a `flisp-machine.toml`:
+///
+/// ```toml
+/// [memory]
+/// ram = ["40-ef"]     # writable regions; everything else outside ROM and devices is read only
+/// rom = ["00-3f"]
+/// code = []           # regions where writes are logged as self-modifying code
+/// stack = "80-df"     # SP must stay inside this region
+///
+/// [[device]]
+/// type = "switches"   # switches, leds, seven-segment, keypad, timer or console
+/// at = "fb"           # first address, or every address as "fb-fb"
+/// value = 0x0f        # initial switch positions
+///
+/// [[device]]
+/// type = "timer"
+/// at = "f4"
+/// irq = false         # leave the IRQ output unconnected
+/// ```
+///
+/// Addresses are hex, written as strings like `"f0"` or `"$f0"`, or as integers.
+/// A device given only its first address occupies as many addresses as it has registers;
+/// a device given a range occupies exactly that range.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Machine {
+    pub memory: Memory,
+    pub devices: Vec<DeviceConfig>,
+}
+
+/// The `[memory]` table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Memory {
+    /// Writable regions. When empty, every address outside ROM and devices is RAM.
+    #[serde(default, deserialize_with = "regions")]
+    pub ram: Vec<RangeInclusive<u8>>,
+    #[serde(default, deserialize_with = "regions")]
+    pub rom: Vec<RangeInclusive<u8>>,
+    #[serde(default, deserialize_with = "regions")]
+    pub code: Vec<RangeInclusive<u8>>,
+    #[serde(default, deserialize_with = "stack")]
+    pub stack: Option<RangeInclusive<u8>>,
+}
+
+/// A device to attach and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceConfig {
+    pub kind: DeviceKind,
+    pub range: RangeInclusive<u8>,
+    /// Whether the device's IRQ output is connected to the CPU
+    pub irq: bool,
+}
+
+/// The lab board devices a machine description can attach, with their initial state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceKind {
+    Switches { value: u8 },
+    Leds,
+    SevenSegment { digits: usize },
+    Keypad,
+    Timer,
+    Console { input: Vec<u8> },
+}
+
+impl DeviceKind {
+    /// Name used for the device in machine descriptions
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Switches { .. } => "switches",
+            Self::Leds => "leds",
+            Self::SevenSegment { .. } => "seven-segment",
+            Self::Keypad => "keypad",
+            Self::Timer => "timer",
+            Self::Console { .. } => "console",
+        }
+    }
+
+    /// Number of addresses the device occupies
+    pub fn registers(&self) -> u8 {
+        match self {
+            Self::Switches { .. } | Self::Leds => 1,
+            Self::SevenSegment { .. } | Self::Keypad | Self::Console { .. } => 2,
+            Self::Timer => 4,
+        }
+    }
+
+    /// Returns: the device in its initial state, or an error for a seven-segment display
+    /// with an unsupported number of digits
+    pub fn build(&self) -> Result<Box<dyn Device>, DigitCountError> {
+        Ok(match self {
+            Self::Switches { value } => Box::new(Switches::new(*value)),
+            Self::Leds => Box::new(Leds::default()),
+            Self::SevenSegment { digits } => Box::new(SevenSegment::new(*digits)?),
+            Self::Keypad => Box::new(Keypad::default()),
+            Self::Timer => Box::new(Timer::default()),
+            Self::Console { input } => Box::new(Console::with_input(input)),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum MachineError {
+    IOError(std::io::Error),
+    /// The file is not valid TOML or does not describe a machine. `line` is 1-based.
+    Invalid {
+        line: Option<usize>,
+        message: String,
+    },
+    /// A device could not be mapped where the machine places it
+    Bus(BusError),
+    /// A seven-segment display was given an unsupported number of digits
+    SevenSegment(DigitCountError),
+}
+
+impl fmt::Display for MachineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOError(e) => write!(f, "{}", e),
+            Self::Invalid {
+                line: Some(line),
+                message,
+            } => write!(f, "line {}: {}", line, message),
+            Self::Invalid {
+                line: None,
+                message,
+            } => write!(f, "{}", message),
+            Self::Bus(e) => write!(f, "{}", e),
+            Self::SevenSegment(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MachineError {}
+
+impl Machine {
+    pub fn parse(src: &str) -> Result<Self, MachineError> {
+        let invalid = |span: Option<Range<usize>>, message: String| MachineError::Invalid {
+            line: span.map(|span| src[..span.start].matches('\n').count() + 1),
+            message,
+        };
+        let file: MachineFile = toml::from_str(src)
+            .map_err(|error| invalid(error.span(), error.message().trim_end().to_string()))?;
+
+        let mut devices: Vec<DeviceConfig> = Vec::new();
+        for entry in file.device {
+            let at = entry.at.span();
+            let device = entry
+                .resolve()
+                .map_err(|(span, message)| invalid(Some(span), message))?;
+            if let Some(other) = devices
+                .iter()
+                .find(|other| overlaps(&other.range, &device.range))
+            {
+                let message = format!(
+                    "device at {:02x}-{:02x} overlaps device at {:02x}-{:02x}",
+                    device.range.start(),
+                    device.range.end(),
+                    other.range.start(),
+                    other.range.end()
+                );
+                return Err(invalid(Some(at), message));
+            }
+            devices.push(device);
+        }
+        Ok(Self {
+            memory: file.memory,
+            devices,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, MachineError> {
+        let src = std::fs::read_to_string(path).map_err(MachineError::IOError)?;
+        Self::parse(&src)
+    }
+}
+
+/// A machine description as written, before its devices are checked
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MachineFile {
+    #[serde(default)]
+    memory: Memory,
+    #[serde(default)]
+    device: Vec<DeviceEntry>,
+}
+
+/// A `[[device]]` table as written. Settings keep their position in the file, so a
+/// mistake can be reported on its own line.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeviceEntry {
+    #[serde(rename = "type")]
+    kind: DeviceType,
+    at: Spanned<Region>,
+    #[serde(default = "connected")]
+    irq: bool,
+    value: Option<Spanned<u8>>,
+    digits: Option<Spanned<usize>>,
+    input: Option<Spanned<String>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum DeviceType {
+    Switches,
+    Leds,
+    SevenSegment,
+    Keypad,
+    Timer,
+    Console,
+}
+
+impl DeviceEntry {
+    /// Returns: the device, or the span of the first setting that does not fit it with
+    /// what is wrong
+    fn resolve(self) -> Result<DeviceConfig, (Range<usize>, String)> {
+        let Self {
+            kind: device_type,
+            at,
+            irq,
+            value,
+            digits,
+            input,
+        } = self;
+        let settings = [
+            (
+                "value",
+                value.as_ref().map(Spanned::span),
+                DeviceType::Switches,
+            ),
+            (
+                "digits",
+                digits.as_ref().map(Spanned::span),
+                DeviceType::SevenSegment,
+            ),
+            (
+                "input",
+                input.as_ref().map(Spanned::span),
+                DeviceType::Console,
+            ),
+        ];
+        let kind = match device_type {
+            DeviceType::Switches => DeviceKind::Switches {
+                value: value.map_or(0, Spanned::into_inner),
+            },
+            DeviceType::Leds => DeviceKind::Leds,
+            DeviceType::SevenSegment => match digits {
+                Some(digits) if !(1..=MAX_DIGITS).contains(digits.get_ref()) => {
+                    return Err((
+                        digits.span(),
+                        DigitCountError(*digits.get_ref()).to_string(),
+                    ));
+                }
+                digits => DeviceKind::SevenSegment {
+                    digits: digits.map_or(DEFAULT_DIGITS, Spanned::into_inner),
+                },
+            },
+            DeviceType::Keypad => DeviceKind::Keypad,
+            DeviceType::Timer => DeviceKind::Timer,
+            DeviceType::Console => DeviceKind::Console {
+                input: input.map_or(Vec::new(), |input| input.into_inner().into_bytes()),
+            },
+        };
+        for (setting, span, owner) in settings {
+            if let Some(span) = span
+                && owner != device_type
+            {
+                return Err((
+                    span,
+                    format!("{} has no `{}` setting", kind.name(), setting),
+                ));
+            }
+        }
+
+        let registers = kind.registers();
+        let span = at.span();
+        let too_small = || {
+            (
+                span.clone(),
+                format!("{} needs {} addresses", kind.name(), registers),
+            )
+        };
+        let range = match at.into_inner() {
+            Region::Address(start) => {
+                let end = start.checked_add(registers - 1).ok_or_else(too_small)?;
+                start..=end
+            }
+            Region::Range(range) if range.len() < registers as usize => return Err(too_small()),
+            Region::Range(range) => range,
+        };
+        Ok(DeviceConfig { kind, range, irq })
+    }
+}
+
+/// An address or range written as `"f0"`, `"$f0"` or `"f0-f3"`, or an integer address
+enum Region {
+    Address(u8),
+    Range(RangeInclusive<u8>),
+}
+
+impl Region {
+    fn range(self) -> RangeInclusive<u8> {
+        match self {
+            Self::Address(adr) => adr..=adr,
+            Self::Range(range) => range,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Region {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = Region;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "an address like \"f0\" or a range like \"f0-f3\"")
+            }
+
+            fn visit_i64<E: de::Error>(self, n: i64) -> Result<Region, E> {
+                u8::try_from(n)
+                    .map(Region::Address)
+                    .map_err(|_| E::custom(format!("{} is not an address", n)))
+            }
+
+            fn visit_u64<E: de::Error>(self, n: u64) -> Result<Region, E> {
+                u8::try_from(n)
+                    .map(Region::Address)
+                    .map_err(|_| E::custom(format!("{} is not an address", n)))
+            }
+
+            fn visit_str<E: de::Error>(self, text: &str) -> Result<Region, E> {
+                let address = |text: &str| {
+                    u8::from_str_radix(text.trim().trim_start_matches('$'), 16)
+                        .map_err(|_| E::custom(format!("{} is not a hex address", text)))
+                };
+                let Some((start, end)) = text.split_once('-') else {
+                    return address(text).map(Region::Address);
+                };
+                let (start, end) = (address(start)?, address(end)?);
+                if start > end {
+                    return Err(E::custom(format!("region {} is empty", text)));
+                }
+                Ok(Region::Range(start..=end))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// One region or an array of them
+fn regions<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<RangeInclusive<u8>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(Region),
+        Many(Vec<Region>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(region) => vec![region.range()],
+        OneOrMany::Many(regions) => regions.into_iter().map(Region::range).collect(),
+    })
+}
+
+fn stack<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<RangeInclusive<u8>>, D::Error> {
+    Region::deserialize(deserializer).map(|region| Some(region.range()))
+}
+
+fn connected() -> bool {
+    true
+}
+
+impl Emulator {
+    /// An emulator with the memory map and devices of `machine`. Devices are attached in
+    /// the order they are listed.
+    pub fn with_machine(machine: &Machine) -> Result<Self, MachineError> {
+        let mut emu = Self::default();
+        for device in &machine.devices {
+            let built = device.kind.build().map_err(MachineError::SevenSegment)?;
+            let id = emu
+                .attach_device(device.range.clone(), built)
+                .map_err(MachineError::Bus)?;
+            emu.bus.set_irq_connected(id, device.irq);
+        }
+
+        if !machine.memory.ram.is_empty() {
+            let read_only: Vec<u8> = (0..=0xff)
+                .filter(|&adr| {
+                    !machine.memory.ram.iter().any(|ram| ram.contains(&adr))
+                        && emu.bus.device_at(adr).is_none()
+                })
+                .collect();
+            for adr in read_only {
+                emu.protect(adr..=adr, Protection::Rom);
+            }
+        }
+        for range in &machine.memory.rom {
+            emu.protect(range.clone(), Protection::Rom);
+        }
+        for range in &machine.memory.code {
+            emu.protect(range.clone(), Protection::Code);
+        }
+        if let Some(stack) = &machine.memory.stack {
+            emu.set_stack_region(stack.clone());
+        }
+        Ok(emu)
+    }
+}
+
+fn overlaps(a: &RangeInclusive<u8>, b: &RangeInclusive<u8>) -> bool {
+    a.start() <= b.end() && b.start() <= a.end()
+}
@@ -0,0 +1,241 @@
+use crate::{
+    Emulator, InFlight, QState, RegisterStore,
+    access::AccessKind,
+    outcome::{ExecutionError, StepOutcome},
+    register::Register,
+};
+
+/// What the control unit did on one clock of an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlState {
+    /// Loaded the opcode into I and advanced PC
+    Fetch,
+    /// Made one memory access: an operand or vector fetch, a read or a write
+    Access(AccessKind),
+    /// Worked inside the CPU without using the bus
+    Internal,
+}
+
+/// An instruction (or interrupt entry) being run one clock at a time.
+///
+/// Each clock re-runs the instruction from the end of its first clock. Accesses made on
+/// earlier clocks return the values they returned then, the next access goes out on the
+/// bus and later ones are only peeked at, so devices, observers and RAM see every access
+/// on its own clock. Once all accesses are made, the remaining clocks are internal and
+/// the results are written back on the last one.
+pub(crate) struct MicroSequence {
+    in_flight: InFlight,
+    /// Registers at the end of the first clock, where every re-run starts
+    start: RegisterStore,
+    /// Values returned by the accesses made so far, in order
+    values: Vec<u8>,
+    /// Registers the instruction leaves behind, known once all its accesses are made
+    result: Option<RegisterStore>,
+    /// Clocks completed
+    clock: u32,
+    state: ControlState,
+}
+
+/// Serves the accesses of an instruction being re-run by the micro-stepper
+pub(crate) struct Replay {
+    values: Vec<u8>,
+    next: usize,
+    /// New accesses that may still go out on the bus this clock
+    budget: usize,
+    /// Kind of the last access made this clock, with the registers right after it
+    made: Option<(AccessKind, RegisterStore)>,
+    /// Whether the instruction wanted more accesses than this clock allows
+    overrun: bool,
+}
+
+impl Replay {
+    /// Records an access that went out on the bus
+    pub(crate) fn accessed(&mut self, kind: AccessKind, value: u8, reg: RegisterStore) {
+        self.values.push(value);
+        self.next += 1;
+        self.budget -= 1;
+        self.made = Some((kind, reg));
+    }
+}
+
+impl Emulator {
+    /// Current Q-state of the control unit
+    pub fn q_state(&self) -> QState {
+        self.q_state
+    }
+
+    /// Progress through the instruction being micro-stepped.
+    ///
+    /// Returns: (clocks completed, clocks in the instruction), or `None` between instructions
+    pub fn instruction_clock(&self) -> Option<(u32, u32)> {
+        self.micro
+            .as_ref()
+            .map(|seq| (seq.clock, seq.in_flight.clocks))
+    }
+
+    /// What the control unit did on the last clock of the instruction being micro-stepped.
+    ///
+    /// Returns: `None` between instructions
+    pub fn control_state(&self) -> Option<ControlState> {
+        self.micro.as_ref().map(|seq| seq.state)
+    }
+
+    /// Advances the CPU by a single clock cycle.
+    ///
+    /// The first clock of an instruction is the fetch, which loads I and advances PC. Each
+    /// following clock makes the instruction's next memory access, if any, latching the
+    /// address in TA, read data in LD and written data in R. Devices tick every clock.
+    /// The instruction's results are written back, and it is recorded and checked, on its
+    /// last clock.
+    ///
+    /// Returns: the outcome of the instruction on its last clock, `None` on earlier clocks
+    pub fn micro_step(&mut self) -> Result<Option<StepOutcome>, ExecutionError> {
+        if self.micro.is_none() {
+            if self.q_state == QState::Reset {
+                return self.step().map(Some);
+            }
+            let in_flight = self.begin_instruction()?;
+            self.micro = Some(MicroSequence {
+                in_flight,
+                start: self.reg,
+                values: Vec::new(),
+                result: None,
+                clock: 0,
+                state: ControlState::Fetch,
+            });
+        }
+        self.advance_micro_sequence().transpose()
+    }
+
+    /// Runs one clock of the instruction being micro-stepped
+    ///
+    /// Returns: the instruction's result on its last clock
+    fn advance_micro_sequence(&mut self) -> Option<Result<StepOutcome, ExecutionError>> {
+        let mut seq = self.micro.take()?;
+        let last = seq.clock + 1 >= seq.in_flight.clocks;
+        let fetched = seq.clock == 0 && seq.in_flight.opcode.is_some();
+        if !fetched {
+            seq.state = self.run_clock(&mut seq, last);
+        }
+        seq.clock += 1;
+        self.advance_clock(1);
+
+        if !last {
+            self.micro = Some(seq);
+            return None;
+        }
+        if let Some(result) = seq.result {
+            self.reg = result;
+        }
+        Some(self.finish_instruction(seq.in_flight))
+    }
+
+    /// Re-runs the instruction, letting its next access out on the bus, or all remaining
+    /// ones on the `last` clock, and leaves the registers as they stand after it
+    fn run_clock(&mut self, seq: &mut MicroSequence, last: bool) -> ControlState {
+        if seq.result.is_some() {
+            return ControlState::Internal;
+        }
+        let shown = self.reg;
+        self.reg = seq.start;
+        self.replay = Some(Replay {
+            values: std::mem::take(&mut seq.values),
+            next: 0,
+            budget: if last { usize::MAX } else { 1 },
+            made: None,
+            overrun: false,
+        });
+        (seq.in_flight.execute)(self);
+        let replay = self.replay.take().unwrap();
+        seq.values = replay.values;
+
+        if !replay.overrun {
+            seq.result = Some(self.reg);
+        }
+        self.reg = match replay.made {
+            Some((_, reg)) => reg,
+            None => shown,
+        };
+        match replay.made {
+            Some((kind, _)) => ControlState::Access(kind),
+            None => ControlState::Internal,
+        }
+    }
+
+    /// Answers an access of an instruction being re-run without using the bus: with the
+    /// value it returned on an earlier clock, or a peek once this clock's access is made.
+    ///
+    /// Returns: `None` if the access should go out on the bus
+    pub(crate) fn replayed_access(
+        &mut self,
+        kind: AccessKind,
+        address: u8,
+        store: Option<u8>,
+    ) -> Option<u8> {
+        let replay = self.replay.as_mut()?;
+        if let Some(&value) = replay.values.get(replay.next) {
+            replay.next += 1;
+            return Some(value);
+        }
+        if replay.budget > 0 {
+            return None;
+        }
+        replay.overrun = true;
+        Some(match (kind, store) {
+            (AccessKind::Write, Some(value)) => value,
+            _ => self.bus.peek(address),
+        })
+    }
+
+    /// Runs the rest of a micro-stepped instruction
+    ///
+    /// Returns: the instruction's result, or `None` if no instruction was in progress
+    pub(crate) fn finish_micro_sequence(&mut self) -> Option<Result<StepOutcome, ExecutionError>> {
+        loop {
+            self.micro.as_ref()?;
+            if let Some(result) = self.advance_micro_sequence() {
+                return Some(result);
+            }
+        }
+    }
+
+    /// Abandons a micro-stepped instruction, putting RAM and registers back as they were
+    /// before its first clock. Devices keep the accesses and ticks they have seen.
+    ///
+    /// Returns: `false` if no instruction was in progress
+    pub(crate) fn abort_micro_sequence(&mut self) -> bool {
+        let Some(seq) = self.micro.take() else {
+            return false;
+        };
+        for access in self.accesses.iter().rev() {
+            if access.kind == AccessKind::Write && self.bus.device_at(access.address).is_none() {
+                self.bus.poke_ram(access.address, access.previous);
+            }
+        }
+        self.accesses.clear();
+        self.reg = seq.in_flight.before;
+        self.clk_count = seq.in_flight.cycle;
+        self.q_state = QState::Fetch;
+        true
+    }
+
+    /// Registers, clock count and RAM at the start of the instruction being micro-stepped,
+    /// or the current state between instructions
+    pub(crate) fn instruction_boundary(&self) -> (RegisterStore, QState, u32, [Register; 256]) {
+        let Some(seq) = &self.micro else {
+            return (self.reg, self.q_state, self.clk_count, self.bus.ram);
+        };
+        let mut ram = self.bus.ram;
+        for access in self.accesses.iter().rev() {
+            if access.kind == AccessKind::Write && self.bus.device_at(access.address).is_none() {
+                ram[access.address as usize].set(access.previous);
+            }
+        }
+        (
+            seq.in_flight.before,
+            QState::Fetch,
+            seq.in_flight.cycle,
+            ram,
+        )
+    }
+}
@@ -0,0 +1,98 @@
+use std::any::Any;
+
+use crate::{Emulator, RegisterStore, register::VisibleRegister, trace::TraceRegisters};
+
+/// Callbacks for emulator events, for tools that embed the emulator.
+///
+/// Every hook has an empty default, so an observer only implements the events it needs.
+/// Memory hooks see CPU accesses only, not changes made through the API.
+pub trait EmulatorObserver: Any + Send {
+    /// An opcode, operand or vector byte was read from the instruction stream
+    fn on_fetch(&mut self, _address: u8, _value: u8) {}
+
+    fn on_memory_read(&mut self, _address: u8, _value: u8) {}
+
+    fn on_memory_write(&mut self, _address: u8, _value: u8, _previous: u8) {}
+
+    /// A register was changed by an instruction or an interrupt entry. Called before
+    /// `on_instruction_executed` or `on_interrupt` for the same step.
+    fn on_register_change(&mut self, _register: VisibleRegister, _old: u8, _new: u8) {}
+
+    /// The instruction `opcode` at `pc` has completed
+    fn on_instruction_executed(
+        &mut self,
+        _pc: u8,
+        _opcode: u8,
+        _before: &TraceRegisters,
+        _after: &TraceRegisters,
+    ) {
+    }
+
+    /// PC was loaded from the reset vector
+    fn on_reset(&mut self, _pc: u8) {}
+
+    /// An interrupt was taken at `pc` and PC now points at `handler`
+    fn on_interrupt(&mut self, _pc: u8, _handler: u8) {}
+
+    /// A message was added to the debug log
+    fn on_debug_log(&mut self, _msg: &str) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(usize);
+
+impl Emulator {
+    /// Registers `observer`. Observers are called in the order they were added.
+    pub fn add_observer(&mut self, observer: Box<dyn EmulatorObserver>) -> ObserverId {
+        self.observers.push(Some(observer));
+        ObserverId(self.observers.len() - 1)
+    }
+
+    pub fn remove_observer(&mut self, id: ObserverId) -> Option<Box<dyn EmulatorObserver>> {
+        self.observers.get_mut(id.0)?.take()
+    }
+
+    /// The observer `id` downcast to its concrete type
+    pub fn observer_as<T: EmulatorObserver>(&self, id: ObserverId) -> Option<&T> {
+        let observer: &dyn Any = self.observers.get(id.0)?.as_deref()?;
+        observer.downcast_ref()
+    }
+
+    pub fn observer_as_mut<T: EmulatorObserver>(&mut self, id: ObserverId) -> Option<&mut T> {
+        let observer: &mut dyn Any = self.observers.get_mut(id.0)?.as_deref_mut()?;
+        observer.downcast_mut()
+    }
+
+    pub(crate) fn notify(&mut self, mut event: impl FnMut(&mut dyn EmulatorObserver)) {
+        for observer in self.observers.iter_mut().flatten() {
+            event(observer.as_mut());
+        }
+    }
+
+    /// Reports the registers changed since `before`, then the instruction itself.
+    /// `opcode` is `None` for an interrupt entry.
+    pub(crate) fn notify_step(&mut self, pc: u8, opcode: Option<u8>, before: &RegisterStore) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let before = TraceRegisters::from(before);
+        let after = TraceRegisters::from(&self.reg);
+        for (register, old, new) in [
+            (VisibleRegister::A, before.a, after.a),
+            (VisibleRegister::X, before.x, after.x),
+            (VisibleRegister::Y, before.y, after.y),
+            (VisibleRegister::SP, before.sp, after.sp),
+            (VisibleRegister::PC, before.pc, after.pc),
+            (VisibleRegister::CC, before.cc, after.cc),
+        ] {
+            if old != new {
+                self.notify(|observer| observer.on_register_change(register, old, new));
+            }
+        }
+        match opcode {
+            Some(opcode) => self
+                .notify(|observer| observer.on_instruction_executed(pc, opcode, &before, &after)),
+            None => self.notify(|observer| observer.on_interrupt(pc, after.pc)),
+        }
+    }
+}
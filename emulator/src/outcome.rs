@@ -0,0 +1,76 @@
+use std::fmt;
+
+use crate::watchpoint::WatchpointHit;
+
+/// Result of a successful `Emulator::step`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    Executed,
+    /// The instruction executed and PC now rests on an enabled breakpoint whose condition held
+    BreakpointHit(u8),
+    /// The instruction executed and triggered a watchpoint
+    WatchpointHit(WatchpointHit),
+    /// An interrupt was taken instead of executing the instruction at `pc`.
+    /// PC now points at `handler`.
+    Interrupt {
+        pc: u8,
+        handler: u8,
+    },
+}
+
+/// How SP left the stack region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackFault {
+    /// A push or call went below the bottom of the region
+    Overflow,
+    /// A pull or return went above the top of the region
+    Underflow,
+    /// SP was loaded or moved to an address outside the region
+    OutOfRegion,
+}
+
+/// Why the CPU could not execute an instruction. Memory and registers are left as they were
+/// when the fault occurred so they can be inspected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionError {
+    /// `opcode` at `pc` is not a FLISP instruction. PC is left pointing at it.
+    InvalidOpcode { pc: u8, opcode: u8 },
+    /// A previous fault stopped the CPU. Only `reset` resumes execution.
+    Halted { pc: u8, opcode: u8 },
+    /// The instruction at `pc` moved SP out of the stack region. The instruction has
+    /// completed and execution may continue.
+    Stack { pc: u8, sp: u8, fault: StackFault },
+    /// The instruction at `pc` wrote to ROM at `address`. The write was dropped, the rest
+    /// of the instruction completed and execution may continue.
+    WriteProtected { pc: u8, address: u8 },
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOpcode { pc, opcode } => {
+                write!(f, "Invalid instruction {:02x} at {:02x}", opcode, pc)
+            }
+            Self::Halted { pc, opcode } => write!(
+                f,
+                "CPU halted after invalid instruction {:02x} at {:02x}",
+                opcode, pc
+            ),
+            Self::Stack { pc, sp, fault } => {
+                let what = match fault {
+                    StackFault::Overflow => "Stack overflow",
+                    StackFault::Underflow => "Stack underflow",
+                    StackFault::OutOfRegion => "SP left the stack region",
+                };
+                write!(f, "{} at {:02x}: SP is {:02x}", what, pc, sp)
+            }
+            Self::WriteProtected { pc, address } => write!(
+                f,
+                "Write to ROM at {:02x} by instruction at {:02x}",
+                address, pc
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionError {}
@@ -0,0 +1,256 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use flisp_core::opcode;
+
+use crate::{
+    Emulator,
+    call_stack::{CALL_OPCODES, RTS},
+};
+
+/// Executions and cycles counted for one address, opcode or label
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProfileCount {
+    pub executions: u64,
+    pub cycles: u64,
+}
+
+impl ProfileCount {
+    fn add(&mut self, cycles: u64) {
+        self.executions += 1;
+        self.cycles += cycles;
+    }
+}
+
+/// Execution counts and cycles per address and per opcode, collected while profiling
+#[derive(Debug, Clone)]
+pub struct Profile {
+    by_address: [ProfileCount; 256],
+    by_opcode: [ProfileCount; 256],
+    /// Calls and inclusive cycles per subroutine entry address
+    subroutines: BTreeMap<u8, ProfileCount>,
+    /// (subroutine address, clock count at the call) for calls that have not returned
+    open_calls: Vec<(u8, u64)>,
+    interrupt_cycles: u64,
+    total_cycles: u64,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            by_address: [ProfileCount::default(); 256],
+            by_opcode: [ProfileCount::default(); 256],
+            subroutines: BTreeMap::new(),
+            open_calls: Vec::new(),
+            interrupt_cycles: 0,
+            total_cycles: 0,
+        }
+    }
+}
+
+impl Profile {
+    /// Instructions executed at `adr` and the cycles they took
+    pub fn at(&self, adr: u8) -> ProfileCount {
+        self.by_address[adr as usize]
+    }
+
+    pub fn for_opcode(&self, opcode: u8) -> ProfileCount {
+        self.by_opcode[opcode as usize]
+    }
+
+    /// Whether an instruction starting at `adr` ever ran
+    pub fn executed(&self, adr: u8) -> bool {
+        self.by_address[adr as usize].executions > 0
+    }
+
+    /// Addresses of every instruction that ran, in ascending order
+    pub fn executed_addresses(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=0xff).filter(|&adr| self.executed(adr))
+    }
+
+    /// Cycles spent in instructions and interrupt entries
+    pub fn total_cycles(&self) -> u64 {
+        self.total_cycles
+    }
+
+    pub fn interrupt_cycles(&self) -> u64 {
+        self.interrupt_cycles
+    }
+
+    /// The `count` addresses that used the most cycles, busiest first
+    pub fn hot_spots(&self, count: usize) -> Vec<(u8, ProfileCount)> {
+        let mut spots: Vec<_> = self
+            .executed_addresses()
+            .map(|adr| (adr, self.at(adr)))
+            .collect();
+        spots.sort_by(|a, b| b.1.cycles.cmp(&a.1.cycles).then(a.0.cmp(&b.0)));
+        spots.truncate(count);
+        spots
+    }
+
+    /// Counts per mnemonic, summed over addressing modes
+    pub fn by_mnemonic(&self) -> BTreeMap<&'static str, ProfileCount> {
+        let mut counts = BTreeMap::<_, ProfileCount>::new();
+        for (op, count) in self.by_opcode.iter().enumerate() {
+            if count.executions == 0 {
+                continue;
+            }
+            let Some(info) = opcode::info(op as u8) else {
+                continue;
+            };
+            let entry = counts.entry(info.mnemonic).or_default();
+            entry.executions += count.executions;
+            entry.cycles += count.cycles;
+        }
+        counts
+    }
+
+    /// Calls and inclusive cycles per subroutine, keyed by entry address. Only calls that
+    /// have returned are counted.
+    pub fn subroutines(&self) -> &BTreeMap<u8, ProfileCount> {
+        &self.subroutines
+    }
+
+    /// Attributes each address to the closest label at or below it.
+    ///
+    /// Returns: counts per label name. Code before the first label is listed under `""`.
+    pub fn by_label<'a>(
+        &self,
+        labels: &'a BTreeMap<String, u8>,
+    ) -> BTreeMap<&'a str, ProfileCount> {
+        let mut sorted: Vec<_> = labels
+            .iter()
+            .map(|(name, &adr)| (adr, name.as_str()))
+            .collect();
+        sorted.sort();
+
+        let mut counts = BTreeMap::<_, ProfileCount>::new();
+        for adr in self.executed_addresses() {
+            let label = sorted
+                .iter()
+                .rev()
+                .find(|(start, _)| *start <= adr)
+                .map_or("", |(_, name)| *name);
+            let entry = counts.entry(label).or_default();
+            entry.executions += self.at(adr).executions;
+            entry.cycles += self.at(adr).cycles;
+        }
+        counts
+    }
+
+    /// Writes a plain text summary: totals, hot spots, mnemonics, and when `labels` are
+    /// given, cycles per label and per subroutine
+    pub fn write_report<W: Write>(
+        &self,
+        mut out: W,
+        labels: Option<&BTreeMap<String, u8>>,
+    ) -> io::Result<()> {
+        let label_at = |adr: u8| {
+            labels
+                .and_then(|labels| labels.iter().find(|(_, a)| **a == adr))
+                .map_or(String::new(), |(name, _)| format!(" {}", name))
+        };
+
+        writeln!(out, "Total cycles: {}", self.total_cycles)?;
+        if self.interrupt_cycles > 0 {
+            writeln!(out, "Interrupt entry cycles: {}", self.interrupt_cycles)?;
+        }
+        writeln!(
+            out,
+            "Instructions covered: {}",
+            self.executed_addresses().count()
+        )?;
+
+        writeln!(out, "\nHot spots:")?;
+        for (adr, count) in self.hot_spots(10) {
+            writeln!(
+                out,
+                "  {:02x}{:<12} {:>8} cycles {:>8} runs",
+                adr,
+                label_at(adr),
+                count.cycles,
+                count.executions
+            )?;
+        }
+
+        writeln!(out, "\nInstructions:")?;
+        for (mnemonic, count) in self.by_mnemonic() {
+            writeln!(
+                out,
+                "  {:<13} {:>8} cycles {:>8} runs",
+                mnemonic, count.cycles, count.executions
+            )?;
+        }
+
+        if let Some(labels) = labels {
+            writeln!(out, "\nLabels:")?;
+            for (label, count) in self.by_label(labels) {
+                let label = if label.is_empty() { "(none)" } else { label };
+                writeln!(out, "  {:<13} {:>8} cycles", label, count.cycles)?;
+            }
+        }
+
+        if !self.subroutines.is_empty() {
+            writeln!(out, "\nSubroutines (including callees):")?;
+            for (adr, count) in &self.subroutines {
+                writeln!(
+                    out,
+                    "  {:02x}{:<11} {:>8} cycles {:>8} calls",
+                    adr,
+                    label_at(*adr),
+                    count.cycles,
+                    count.executions
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Emulator {
+    /// Starts counting executions and cycles. Counting continues from any profile
+    /// already collected.
+    pub fn start_profiling(&mut self) {
+        self.profile.get_or_insert_default();
+    }
+
+    /// Stops counting and hands back the profile collected so far
+    pub fn stop_profiling(&mut self) -> Option<Profile> {
+        self.profile.take()
+    }
+
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
+    pub(crate) fn record_profile(&mut self, pc: u8, opcode: Option<u8>, cycle: u32) {
+        let clk_count = self.clk_count as u64;
+        let new_pc = self.reg.pc.get();
+        let Some(profile) = &mut self.profile else {
+            return;
+        };
+        let cycles = clk_count - cycle as u64;
+        profile.total_cycles += cycles;
+
+        let Some(opcode) = opcode else {
+            profile.interrupt_cycles += cycles;
+            return;
+        };
+        profile.by_address[pc as usize].add(cycles);
+        profile.by_opcode[opcode as usize].add(cycles);
+
+        if CALL_OPCODES.contains(&opcode) {
+            profile.open_calls.push((new_pc, cycle as u64));
+        } else if opcode == RTS
+            && let Some((target, start)) = profile.open_calls.pop()
+        {
+            profile
+                .subroutines
+                .entry(target)
+                .or_default()
+                .add(clk_count - start);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::ops::RangeInclusive;
+
+use crate::{Emulator, access::AccessKind, outcome::ExecutionError};
+
+/// How the CPU treats writes to a protected address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protection {
+    /// Writes are dropped and fault
+    Rom,
+    /// Writes go through but are logged as a warning
+    Code,
+}
+
+impl Emulator {
+    pub fn protect(&mut self, range: RangeInclusive<u8>, protection: Protection) {
+        for adr in range {
+            self.protection[adr as usize] = Some(protection);
+        }
+    }
+
+    /// Protects every address set in `mask`, such as `AssemblyOutput::initialized`
+    pub fn protect_bytes(&mut self, mask: &[bool; 256], protection: Protection) {
+        for (adr, _) in mask.iter().enumerate().filter(|(_, set)| **set) {
+            self.protection[adr] = Some(protection);
+        }
+    }
+
+    pub fn unprotect(&mut self, range: RangeInclusive<u8>) {
+        for adr in range {
+            self.protection[adr as usize] = None;
+        }
+    }
+
+    pub fn protection_at(&self, adr: u8) -> Option<Protection> {
+        self.protection[adr as usize]
+    }
+
+    /// Whether the CPU has written `adr` since the last reset
+    pub fn written_at_runtime(&self, adr: u8) -> bool {
+        self.written[adr as usize]
+    }
+
+    pub(crate) fn is_rom(&self, adr: u8) -> bool {
+        self.protection[adr as usize] == Some(Protection::Rom)
+    }
+
+    /// Looks at the accesses of the instruction at `pc`, or of an interrupt entry when
+    /// `executed` is false. Executing bytes written at runtime and writing to code are
+    /// logged.
+    ///
+    /// Returns: an error for the first write to ROM
+    pub(crate) fn check_protection(
+        &mut self,
+        pc: u8,
+        executed: bool,
+    ) -> Result<(), ExecutionError> {
+        for i in 0..self.accesses.len() {
+            let access = self.accesses[i];
+            let adr = access.address;
+            match access.kind {
+                AccessKind::Fetch if executed && self.written[adr as usize] => {
+                    self.debug_log(format!(
+                        "Executing {:02x}, written at runtime, in instruction at {:02x}",
+                        adr, pc
+                    ));
+                }
+                AccessKind::Write => {
+                    self.written[adr as usize] = true;
+                    if self.protection[adr as usize] == Some(Protection::Code) {
+                        self.debug_log(format!(
+                            "Write to code at {:02x} by instruction at {:02x}",
+                            adr, pc
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.rom_fault(pc)
+    }
+
+    /// Marks the addresses written by the instruction at `pc` without logging anything
+    ///
+    /// Returns: an error for the first write to ROM
+    pub(crate) fn mark_writes(&mut self, pc: u8) -> Result<(), ExecutionError> {
+        for access in &self.accesses {
+            if access.kind == AccessKind::Write {
+                self.written[access.address as usize] = true;
+            }
+        }
+        self.rom_fault(pc)
+    }
+
+    /// Returns: an error if the instruction at `pc` had a write to ROM dropped
+    fn rom_fault(&self, pc: u8) -> Result<(), ExecutionError> {
+        match self.dropped_write {
+            Some(address) => Err(ExecutionError::WriteProtected { pc, address }),
+            None => Ok(()),
+        }
+    }
+}
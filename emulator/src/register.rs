@@ -2,6 +2,30 @@ use std::ops;
 
 use crate::math_utils::{add_c, sub};
 
+/// A register the program can read and write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisibleRegister {
+    A,
+    X,
+    Y,
+    SP,
+    PC,
+    CC,
+}
+
+/// A datapath register the program cannot address directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InternalRegister {
+    /// Result register, holding data on its way to memory
+    R,
+    /// Temporary address register
+    TA,
+    /// Load register, holding operand and read data
+    LD,
+    /// Instruction register
+    I,
+}
+
 #[derive(Default, Copy, Clone, PartialEq, Eq)]
 pub struct Register {
     data: u8,
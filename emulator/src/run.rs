@@ -0,0 +1,120 @@
+use crate::{
+    CCFlag, Emulator, access::AccessKind, breakpoint::StopReason, outcome::StepOutcome,
+    trace::TraceRegisters,
+};
+
+/// When `Emulator::run_until` should stop. Breakpoints, watchpoints and faults always stop
+/// a run; conditions left unset are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunUntil {
+    pub max_cycles: Option<u64>,
+    pub max_instructions: Option<u64>,
+    /// Stop when PC reaches this address
+    pub pc: Option<u8>,
+    /// Stop when the subroutine executing at the start of the run returns. Without a
+    /// recorded call, the run stops with `NotInSubroutine` before executing anything.
+    pub subroutine_return: bool,
+    /// Stop on a branch to itself or on a loop that repeats without changing registers
+    /// or memory. Not checked while interrupts are enabled and an attached device may
+    /// still raise one, since the interrupt can end the loop.
+    pub detect_halt: bool,
+}
+
+/// Registers at the last visit of each address, and the memory epoch at that time
+struct LoopDetector {
+    visits: Box<[Option<(TraceRegisters, u64)>; 256]>,
+    /// Incremented whenever memory or a device is touched
+    epoch: u64,
+}
+
+impl LoopDetector {
+    fn new() -> Self {
+        Self {
+            visits: Box::new([None; 256]),
+            epoch: 0,
+        }
+    }
+}
+
+impl Emulator {
+    /// Executes instructions until one of the `until` conditions holds, a breakpoint or
+    /// watchpoint is hit or the CPU faults. Unless the run waits for a subroutine return
+    /// with no call open, the instruction at the current PC always executes. Without any
+    /// limit set, a program that never stops keeps the run going forever.
+    pub fn run_until(&mut self, until: &RunUntil) -> StopReason {
+        let start_clk = self.clk_count;
+        let depth = self.call_stack.len();
+        if until.subroutine_return && depth == 0 {
+            return StopReason::NotInSubroutine;
+        }
+        let mut detector = until.detect_halt.then(LoopDetector::new);
+        let mut instructions = 0;
+
+        loop {
+            if until
+                .max_instructions
+                .is_some_and(|max| instructions >= max)
+            {
+                return StopReason::StepLimit;
+            }
+            let pc = self.reg.pc.get();
+            let interrupt = match self.step() {
+                Ok(StepOutcome::Executed) => false,
+                Ok(StepOutcome::Interrupt { .. }) => true,
+                Ok(StepOutcome::BreakpointHit(address)) => return StopReason::Breakpoint(address),
+                Ok(StepOutcome::WatchpointHit(hit)) => return StopReason::Watchpoint(hit),
+                Err(error) => return StopReason::Fault(error),
+            };
+            instructions += 1;
+
+            if until.subroutine_return && self.call_stack.len() < depth {
+                return StopReason::Completed;
+            }
+            if until.pc == Some(self.reg.pc.get()) {
+                return StopReason::ReachedPc(self.reg.pc.get());
+            }
+            if let Some(detector) = &mut detector
+                && let Some(reason) = self.detect_halt(detector, pc, interrupt)
+            {
+                return reason;
+            }
+            let cycles = self.clk_count.wrapping_sub(start_clk) as u64;
+            if until.max_cycles.is_some_and(|max| cycles >= max) {
+                return StopReason::CycleLimit;
+            }
+        }
+    }
+
+    /// Checks the step just made from `pc` for a self-branch or a repeated state
+    fn detect_halt(
+        &self,
+        detector: &mut LoopDetector,
+        pc: u8,
+        interrupt: bool,
+    ) -> Option<StopReason> {
+        let changed_memory = self.accesses.iter().any(|access| {
+            (access.kind == AccessKind::Write && access.value != access.previous)
+                || self.bus.device_at(access.address).is_some()
+        });
+        if changed_memory {
+            detector.epoch += 1;
+        }
+
+        let woken_by_device = !self.reg.cc.get(CCFlag::I) && self.bus.may_interrupt();
+        if woken_by_device {
+            return None;
+        }
+        let new_pc = self.reg.pc.get();
+        if new_pc == pc && !interrupt {
+            return Some(StopReason::SelfBranch(pc));
+        }
+
+        let state = (TraceRegisters::from(&self.reg), detector.epoch);
+        let visit = &mut detector.visits[new_pc as usize];
+        if *visit == Some(state) {
+            return Some(StopReason::IdleLoop(new_pc));
+        }
+        *visit = Some(state);
+        None
+    }
+}
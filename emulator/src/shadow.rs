@@ -0,0 +1,68 @@
+use crate::{Emulator, access::AccessKind, register::Register};
+
+/// A read of memory that neither the loaded image nor an earlier write defined
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UninitializedRead {
+    /// Address of the instruction that made the read
+    pub pc: u8,
+    pub address: u8,
+    /// `Fetch` when the byte was executed as part of an instruction
+    pub kind: AccessKind,
+}
+
+impl Emulator {
+    /// Loads a program image, remembering which bytes it defines. Reading or executing
+    /// any other byte before it is written is reported.
+    pub fn load_image(&mut self, data: &[u8; 256], initialized: &[bool; 256]) {
+        for (i, mem) in data.iter().enumerate() {
+            self.bus.ram[i] = Register::new(*mem);
+            self.source_memory[i] = Register::new(*mem);
+        }
+        self.source_initialized = *initialized;
+    }
+
+    /// Whether `adr` was defined by the loaded image, or written by the CPU or the editing
+    /// API since the last reset
+    pub fn is_initialized(&self, adr: u8) -> bool {
+        let adr = adr as usize;
+        self.source_initialized[adr] || self.written[adr] || self.edited[adr]
+    }
+
+    /// Reads of uninitialized memory since the last reset. Each instruction is listed
+    /// once per address.
+    pub fn uninitialized_reads(&self) -> &[UninitializedRead] {
+        &self.uninitialized_reads
+    }
+
+    /// Reports fetches and reads of uninitialized RAM made by the instruction or interrupt
+    /// entry at `pc`. Call before the step's writes are marked.
+    pub(crate) fn check_uninitialized(&mut self, pc: u8) {
+        for i in 0..self.accesses.len() {
+            let access = self.accesses[i];
+            if access.kind == AccessKind::Write
+                || self.is_initialized(access.address)
+                || self.bus.device_at(access.address).is_some()
+            {
+                continue;
+            }
+            let read = UninitializedRead {
+                pc,
+                address: access.address,
+                kind: access.kind,
+            };
+            if self.uninitialized_reads.contains(&read) {
+                continue;
+            }
+            self.uninitialized_reads.push(read);
+            let what = if read.kind == AccessKind::Fetch {
+                "Executing"
+            } else {
+                "Reading"
+            };
+            self.debug_log(format!(
+                "{} uninitialized {:02x} in instruction at {:02x}",
+                what, read.address, pc
+            ));
+        }
+    }
+}
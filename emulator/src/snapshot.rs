@@ -0,0 +1,219 @@
+use std::{fmt, path::Path};
+
+use crate::{CCFlags, Emulator, QState, RegisterStore, register::Register};
+
+/// Extension used for saved machine states
+pub const SNAPSHOT_EXTENSION: &str = "fstate";
+
+const MAGIC: &[u8; 6] = b"FSTATE";
+const VERSION: u8 = 2;
+
+// magic, version, 10 registers, q-state, clock count, memory, source memory, and the
+// initialized, written and edited masks
+const V2_LEN: usize = 6 + 1 + 10 + 1 + 4 + 256 + 256 + 3 * 32;
+
+/// Complete machine state of a paused emulator
+#[derive(Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    reg: RegisterStore,
+    memory: [Register; 256],
+    source_memory: [Register; 256],
+    /// Addresses defined by the loaded image
+    source_initialized: [bool; 256],
+    /// Addresses written by the CPU since the last reset
+    written: [bool; 256],
+    /// Addresses written through the editing API since the last reset
+    edited: [bool; 256],
+    q_state: QState,
+    clk_count: u32,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    IOError(std::io::Error),
+    NotASnapshot,
+    UnsupportedVersion(u8),
+    Truncated {
+        expected: usize,
+        found: usize,
+    },
+    TrailingBytes {
+        expected: usize,
+        found: usize,
+    },
+    /// Snapshots are taken between instructions, so the Q-state is Reset or Fetch
+    InvalidQState(u8),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOError(e) => write!(f, "{}", e),
+            Self::NotASnapshot => write!(f, "not a FLISP state file"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported state file version {}", v),
+            Self::Truncated { expected, found } => write!(
+                f,
+                "state file is truncated: expected {} bytes, found {}",
+                expected, found
+            ),
+            Self::TrailingBytes { expected, found } => write!(
+                f,
+                "state file has trailing data: expected {} bytes, found {}",
+                expected, found
+            ),
+            Self::InvalidQState(q) => write!(f, "invalid Q-state {}", q),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl Snapshot {
+    /// Encodes the snapshot in the versioned `.fstate` format.
+    ///
+    /// Layout (version 2): `FSTATE`, version byte, registers A X Y R I SP PC TA CC LD,
+    /// Q-state, clock count (u32 little endian), 256 bytes of memory, 256 bytes of source
+    /// memory, then 32-byte bit masks of the addresses the image defines, the CPU has
+    /// written and the editing API has written. Bit n of byte k stands for address 8k+n.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(V2_LEN);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        let reg = &self.reg;
+        out.extend_from_slice(&[
+            reg.a.get(),
+            reg.x.get(),
+            reg.y.get(),
+            reg.r.get(),
+            reg.i.get(),
+            reg.sp.get(),
+            reg.pc.get(),
+            reg.ta.get(),
+            reg.cc.data,
+            reg.ld.get(),
+        ]);
+        out.push(self.q_state as u8);
+        out.extend_from_slice(&self.clk_count.to_le_bytes());
+        out.extend(self.memory.iter().map(Register::get));
+        out.extend(self.source_memory.iter().map(Register::get));
+        for mask in [&self.source_initialized, &self.written, &self.edited] {
+            out.extend(pack_mask(mask));
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        if bytes.len() < MAGIC.len() + 1 || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+        let version = bytes[MAGIC.len()];
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        if bytes.len() < V2_LEN {
+            return Err(SnapshotError::Truncated {
+                expected: V2_LEN,
+                found: bytes.len(),
+            });
+        }
+        if bytes.len() > V2_LEN {
+            return Err(SnapshotError::TrailingBytes {
+                expected: V2_LEN,
+                found: bytes.len(),
+            });
+        }
+
+        let body = &bytes[MAGIC.len() + 1..];
+        let (regs, body) = body.split_at(10);
+        let reg = RegisterStore {
+            a: Register::new(regs[0]),
+            x: Register::new(regs[1]),
+            y: Register::new(regs[2]),
+            r: Register::new(regs[3]),
+            i: Register::new(regs[4]),
+            sp: Register::new(regs[5]),
+            pc: Register::new(regs[6]),
+            ta: Register::new(regs[7]),
+            cc: CCFlags::new(regs[8]),
+            ld: Register::new(regs[9]),
+        };
+        let q_state = match body[0] {
+            0 => QState::Reset,
+            1 => QState::Fetch,
+            q => return Err(SnapshotError::InvalidQState(q)),
+        };
+        let clk_count = u32::from_le_bytes(body[1..5].try_into().unwrap());
+        let (memory, body) = body[5..].split_at(256);
+        let (source_memory, masks) = body.split_at(256);
+
+        Ok(Self {
+            reg,
+            memory: std::array::from_fn(|i| Register::new(memory[i])),
+            source_memory: std::array::from_fn(|i| Register::new(source_memory[i])),
+            source_initialized: unpack_mask(&masks[..32]),
+            written: unpack_mask(&masks[32..64]),
+            edited: unpack_mask(&masks[64..]),
+            q_state,
+            clk_count,
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        std::fs::write(path, self.to_bytes()).map_err(SnapshotError::IOError)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SnapshotError> {
+        let bytes = std::fs::read(path).map_err(SnapshotError::IOError)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl Emulator {
+    /// Captures the machine state. Part way through a micro-stepped instruction, the state
+    /// at the start of that instruction is captured.
+    pub fn snapshot(&self) -> Snapshot {
+        let (reg, q_state, clk_count, memory) = self.instruction_boundary();
+        Snapshot {
+            reg,
+            memory,
+            source_memory: self.source_memory,
+            source_initialized: self.source_initialized,
+            written: self.written,
+            edited: self.edited,
+            q_state,
+            clk_count,
+        }
+    }
+
+    /// Replaces the machine state with `snapshot`. Execution history, the call stack and
+    /// any fault are discarded; which memory is initialized or was written at runtime is
+    /// taken from the snapshot. Breakpoints, watchpoints and attached devices are kept.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.reg = snapshot.reg;
+        self.bus.ram = snapshot.memory;
+        self.source_memory = snapshot.source_memory;
+        self.q_state = snapshot.q_state;
+        self.clk_count = snapshot.clk_count;
+        self.fault = None;
+        self.accesses.clear();
+        self.watchpoint_hits.clear();
+        self.history.clear();
+        self.call_stack.clear();
+        self.lowest_sp = None;
+        self.written = snapshot.written;
+        self.source_initialized = snapshot.source_initialized;
+        self.uninitialized_reads.clear();
+        self.edited = snapshot.edited;
+        self.edits.clear();
+        self.micro = None;
+    }
+}
+
+/// Packs one flag per address into 32 bytes, address 8k+n in bit n of byte k
+fn pack_mask(mask: &[bool; 256]) -> [u8; 32] {
+    std::array::from_fn(|k| (0..8).fold(0, |byte, n| byte | (mask[8 * k + n] as u8) << n))
+}
+
+fn unpack_mask(bytes: &[u8]) -> [bool; 256] {
+    std::array::from_fn(|adr| bytes[adr / 8] & (1 << (adr % 8)) != 0)
+}
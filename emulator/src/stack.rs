@@ -0,0 +1,81 @@
+use std::ops::RangeInclusive;
+
+use flisp_core::opcode;
+
+use crate::{
+    Emulator,
+    outcome::{ExecutionError, StackFault},
+};
+
+impl Emulator {
+    /// Guards the stack: SP must stay within `region` or just above it, where it points
+    /// when the stack is empty. Leaving the region faults once SP has been inside it.
+    pub fn set_stack_region(&mut self, region: RangeInclusive<u8>) {
+        self.stack_region = Some(region);
+        self.lowest_sp = None;
+    }
+
+    pub fn clear_stack_region(&mut self) {
+        self.stack_region = None;
+        self.lowest_sp = None;
+    }
+
+    pub fn stack_region(&self) -> Option<RangeInclusive<u8>> {
+        self.stack_region.clone()
+    }
+
+    /// Lowest SP seen inside the stack region since it was set or the CPU was reset
+    pub fn stack_high_water_mark(&self) -> Option<u8> {
+        self.lowest_sp
+    }
+
+    /// Returns: the most bytes the stack has held, or `None` if SP has not been inside
+    /// the stack region
+    pub fn max_stack_depth(&self) -> Option<usize> {
+        let region = self.stack_region.as_ref()?;
+        Some(depth(region, self.lowest_sp?) as usize)
+    }
+
+    /// Checks SP after `opcode` at `pc` executed, or after an interrupt entry when `opcode`
+    /// is `None`, and lowers the high-water mark
+    pub(crate) fn check_stack(
+        &mut self,
+        pc: u8,
+        opcode: Option<u8>,
+        sp_before: u8,
+    ) -> Result<(), ExecutionError> {
+        let Some(region) = &self.stack_region else {
+            return Ok(());
+        };
+        let empty = region.end().wrapping_add(1);
+        let in_region = |sp: u8| region.contains(&sp) || sp == empty;
+
+        let sp = self.reg.sp.get();
+        if in_region(sp) {
+            if self
+                .lowest_sp
+                .is_none_or(|lowest| depth(region, sp) > depth(region, lowest))
+            {
+                self.lowest_sp = Some(sp);
+            }
+            return Ok(());
+        }
+        if sp == sp_before || !in_region(sp_before) {
+            return Ok(());
+        }
+
+        let mnemonic = opcode.and_then(opcode::info).map(|info| info.mnemonic);
+        let fault = match mnemonic {
+            None | Some("PSHA" | "PSHX" | "PSHY" | "PSHC" | "BSR" | "JSR") => StackFault::Overflow,
+            Some("PULA" | "PULX" | "PULY" | "PULC" | "RTS" | "RTI") => StackFault::Underflow,
+            Some(_) => StackFault::OutOfRegion,
+        };
+        Err(ExecutionError::Stack { pc, sp, fault })
+    }
+}
+
+/// Bytes on the stack when SP is `sp`. Counted from the top of the region so that a region
+/// ending at $ff, where the empty stack has SP at $00, works too.
+fn depth(region: &RangeInclusive<u8>, sp: u8) -> u8 {
+    region.end().wrapping_add(1).wrapping_sub(sp)
+}
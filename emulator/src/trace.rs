@@ -0,0 +1,195 @@
+use std::io::{self, Write};
+
+use flisp_core::opcode;
+
+use crate::{Emulator, RegisterStore, access::AccessKind};
+
+/// Programmer-visible registers at one point of a trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceRegisters {
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub sp: u8,
+    pub pc: u8,
+    pub cc: u8,
+}
+
+impl From<&RegisterStore> for TraceRegisters {
+    fn from(reg: &RegisterStore) -> Self {
+        Self {
+            a: reg.a.get(),
+            x: reg.x.get(),
+            y: reg.y.get(),
+            sp: reg.sp.get(),
+            pc: reg.pc.get(),
+            cc: reg.cc.data(),
+        }
+    }
+}
+
+/// One executed instruction, or one interrupt entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceRecord {
+    /// Clock count when the instruction started
+    pub cycle: u32,
+    pub pc: u8,
+    /// `None` for an interrupt entry
+    pub opcode: Option<u8>,
+    pub mnemonic: &'static str,
+    pub before: TraceRegisters,
+    pub after: TraceRegisters,
+    /// (address, value) for every byte written, in execution order
+    pub writes: Vec<(u8, u8)>,
+}
+
+/// Complete record of executed instructions, in execution order.
+///
+/// Steps undone with `step_back` stay in the trace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    records: Vec<TraceRecord>,
+}
+
+impl Trace {
+    pub fn records(&self) -> &[TraceRecord] {
+        &self.records
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Writes the trace as CSV with a header row. Bytes are written as two hex digits,
+    /// and writes as `address=value` pairs separated by `;`.
+    pub fn write_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(
+            out,
+            "cycle,pc,opcode,mnemonic,a,x,y,sp,cc,a_after,x_after,y_after,sp_after,pc_after,cc_after,writes"
+        )?;
+        for record in &self.records {
+            let opcode = record
+                .opcode
+                .map(|op| format!("{:02x}", op))
+                .unwrap_or_default();
+            let (b, a) = (record.before, record.after);
+            let writes: Vec<_> = record
+                .writes
+                .iter()
+                .map(|(adr, val)| format!("{:02x}={:02x}", adr, val))
+                .collect();
+            writeln!(
+                out,
+                "{},{:02x},{},{},{:02x},{:02x},{:02x},{:02x},{:02x},{:02x},{:02x},{:02x},{:02x},{:02x},{:02x},{}",
+                record.cycle,
+                record.pc,
+                opcode,
+                record.mnemonic,
+                b.a,
+                b.x,
+                b.y,
+                b.sp,
+                b.cc,
+                a.a,
+                a.x,
+                a.y,
+                a.sp,
+                a.pc,
+                a.cc,
+                writes.join(";")
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the trace as JSON Lines, one object per record. Bytes are written as numbers.
+    pub fn write_jsonl<W: Write>(&self, mut out: W) -> io::Result<()> {
+        fn regs(r: &TraceRegisters) -> String {
+            format!(
+                r#"{{"a":{},"x":{},"y":{},"sp":{},"pc":{},"cc":{}}}"#,
+                r.a, r.x, r.y, r.sp, r.pc, r.cc
+            )
+        }
+
+        for record in &self.records {
+            let opcode = record
+                .opcode
+                .map_or("null".to_string(), |op| op.to_string());
+            let writes: Vec<_> = record
+                .writes
+                .iter()
+                .map(|(adr, val)| format!(r#"{{"address":{},"value":{}}}"#, adr, val))
+                .collect();
+            writeln!(
+                out,
+                r#"{{"cycle":{},"pc":{},"opcode":{},"mnemonic":"{}","before":{},"after":{},"writes":[{}]}}"#,
+                record.cycle,
+                record.pc,
+                opcode,
+                record.mnemonic,
+                regs(&record.before),
+                regs(&record.after),
+                writes.join(",")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Emulator {
+    /// Starts recording a trace record for every executed instruction.
+    /// Recording continues from any trace already collected.
+    pub fn start_trace(&mut self) {
+        self.trace.get_or_insert_default();
+    }
+
+    /// Stops recording and hands back the trace collected so far
+    pub fn stop_trace(&mut self) -> Option<Trace> {
+        self.trace.take()
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    pub fn trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    pub(crate) fn record_trace(
+        &mut self,
+        pc: u8,
+        opcode: Option<u8>,
+        before: &RegisterStore,
+        cycle: u32,
+    ) {
+        let Some(trace) = &mut self.trace else {
+            return;
+        };
+        let writes = self
+            .accesses
+            .iter()
+            .filter(|access| access.kind == AccessKind::Write)
+            .map(|access| (access.address, access.value))
+            .collect();
+        trace.records.push(TraceRecord {
+            cycle,
+            pc,
+            opcode,
+            mnemonic: opcode
+                .and_then(opcode::info)
+                .map_or("IRQ", |info| info.mnemonic),
+            before: before.into(),
+            after: (&self.reg).into(),
+            writes,
+        });
+    }
+}
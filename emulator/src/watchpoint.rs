@@ -0,0 +1,175 @@
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use crate::{
+    Emulator,
+    access::{AccessKind, MemoryAccess},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    /// Any data read. Instruction fetches are not counted.
+    Read,
+    /// Any write, even one that stores the value already present
+    Write,
+    /// A write that changes the stored value
+    Change,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WatchpointId(u32);
+
+#[derive(Debug, Clone)]
+pub struct Watchpoint {
+    start: u8,
+    end: u8,
+    kind: WatchKind,
+    enabled: bool,
+    hit_count: u32,
+}
+
+impl Watchpoint {
+    pub fn range(&self) -> RangeInclusive<u8> {
+        self.start..=self.end
+    }
+
+    pub fn kind(&self) -> WatchKind {
+        self.kind
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn hit_count(&self) -> u32 {
+        self.hit_count
+    }
+
+    fn matches(&self, access: &MemoryAccess) -> bool {
+        if !self.enabled || !self.range().contains(&access.address) {
+            return false;
+        }
+        match (self.kind, access.kind) {
+            (WatchKind::Read, AccessKind::Read) => true,
+            (WatchKind::Write, AccessKind::Write) => true,
+            (WatchKind::Change, AccessKind::Write) => access.value != access.previous,
+            _ => false,
+        }
+    }
+}
+
+/// A watchpoint that triggered during the last executed instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchpointHit {
+    pub id: WatchpointId,
+    /// Address of the instruction that made the access
+    pub pc: u8,
+    pub access: MemoryAccess,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Watchpoints {
+    entries: BTreeMap<WatchpointId, Watchpoint>,
+    next_id: u32,
+}
+
+impl Watchpoints {
+    pub fn add(&mut self, range: RangeInclusive<u8>, kind: WatchKind) -> WatchpointId {
+        let id = WatchpointId(self.next_id);
+        self.next_id += 1;
+        self.entries.insert(
+            id,
+            Watchpoint {
+                start: *range.start(),
+                end: *range.end(),
+                kind,
+                enabled: true,
+                hit_count: 0,
+            },
+        );
+        id
+    }
+
+    pub fn add_address(&mut self, address: u8, kind: WatchKind) -> WatchpointId {
+        self.add(address..=address, kind)
+    }
+
+    pub fn remove(&mut self, id: WatchpointId) -> Option<Watchpoint> {
+        self.entries.remove(&id)
+    }
+
+    /// Returns `false` if `id` does not exist
+    pub fn enable(&mut self, id: WatchpointId) -> bool {
+        self.set_enabled(id, true)
+    }
+
+    /// Returns `false` if `id` does not exist
+    pub fn disable(&mut self, id: WatchpointId) -> bool {
+        self.set_enabled(id, false)
+    }
+
+    fn set_enabled(&mut self, id: WatchpointId, enabled: bool) -> bool {
+        match self.entries.get_mut(&id) {
+            Some(wp) => {
+                wp.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, id: WatchpointId) -> Option<&Watchpoint> {
+        self.entries.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (WatchpointId, &Watchpoint)> {
+        self.entries.iter().map(|(id, wp)| (*id, wp))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Matches `accesses` against every watchpoint, updating hit counts
+    fn check(&mut self, pc: u8, accesses: &[MemoryAccess], hits: &mut Vec<WatchpointHit>) {
+        for access in accesses {
+            for (id, wp) in self.entries.iter_mut() {
+                if wp.matches(access) {
+                    wp.hit_count += 1;
+                    hits.push(WatchpointHit {
+                        id: *id,
+                        pc,
+                        access: *access,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl Emulator {
+    pub fn watchpoints(&self) -> &Watchpoints {
+        &self.watchpoints
+    }
+
+    pub fn watchpoints_mut(&mut self) -> &mut Watchpoints {
+        &mut self.watchpoints
+    }
+
+    /// Watchpoints triggered by the most recent `step`
+    pub fn watchpoint_hits(&self) -> &[WatchpointHit] {
+        &self.watchpoint_hits
+    }
+
+    pub(crate) fn check_watchpoints(&mut self, pc: u8) {
+        self.watchpoint_hits.clear();
+        if self.watchpoints.is_empty() {
+            return;
+        }
+        self.watchpoints
+            .check(pc, &self.accesses, &mut self.watchpoint_hits);
+    }
+}
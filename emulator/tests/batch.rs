@@ -0,0 +1,106 @@
+mod common;
+use common::load_program;
+
+use emulator::{Emulator, batch::BatchRun, interrupt::IRQ_VECTOR, outcome::ExecutionError};
+
+// $20: LDA #$0a
+// $22: STA $50
+// $24: DECA
+// $25: BNE $22
+// $27: BRA $27
+const COUNTDOWN: &[u8] = &[0xf0, 0x0a, 0xe1, 0x50, 0x08, 0x25, 0xfb, 0x21, 0xfe];
+
+#[test]
+fn batch_runs_match_single_steps() {
+    let mut stepped = load_program(0x20, COUNTDOWN);
+    let mut batched = load_program(0x20, COUNTDOWN);
+    let start = stepped.clk_count();
+    for _ in 0..40 {
+        stepped.step().unwrap();
+    }
+
+    let run = batched.run_instructions(40).unwrap();
+    assert_eq!(run.instructions, 40);
+    assert_eq!(run.cycles, (stepped.clk_count() - start) as u64);
+    assert_eq!(batched.reg_pc().get(), stepped.reg_pc().get());
+    assert_eq!(batched.reg_a().get(), 0);
+    assert_eq!(batched.reg_cc().data(), stepped.reg_cc().data());
+    assert_eq!(batched.memory_at(0x50_u8), 0x01);
+    assert_eq!(batched.history_len(), 0);
+}
+
+#[test]
+fn run_cycles_stops_once_the_budget_is_spent() {
+    let mut emu = load_program(0x20, COUNTDOWN);
+    let start = emu.clk_count();
+
+    let run = emu.run_cycles(10).unwrap();
+    assert!(run.cycles >= 10);
+    assert_eq!(run.cycles, (emu.clk_count() - start) as u64);
+    assert!(run.instructions < 10);
+}
+
+#[test]
+fn batch_runs_stop_at_faults() {
+    // $20: NOP
+    // $21: invalid
+    let mut emu = load_program(0x20, &[0x00, 0x03]);
+
+    assert_eq!(
+        emu.run_instructions(10),
+        Err(ExecutionError::InvalidOpcode {
+            pc: 0x21,
+            opcode: 0x03,
+        })
+    );
+    assert_eq!(
+        emu.fault(),
+        Some(ExecutionError::InvalidOpcode {
+            pc: 0x21,
+            opcode: 0x03
+        })
+    );
+    assert_ne!(emu.run_cycles(1), Ok(BatchRun::default()));
+}
+
+// $20: LDSP #$e0
+// $22: BSR $26
+// $24: BRA $24
+// $26: LDA #$30
+// $28: PSHA
+// $29: RTS         returns to $30 instead of $24
+// $30: BRA $30
+// $40: RTI
+fn unbalanced_return_program() -> Emulator {
+    let mut mem = [0_u8; 256];
+    mem[0x20..0x2a].copy_from_slice(&[0x92, 0xe0, 0x20, 0x02, 0x21, 0xfe, 0xf0, 0x30, 0x10, 0x43]);
+    mem[0x30..0x32].copy_from_slice(&[0x21, 0xfe]);
+    mem[0x40] = 0x44;
+    mem[IRQ_VECTOR as usize] = 0x40;
+    mem[0xff] = 0x20;
+
+    let mut emu = Emulator::default();
+    emu.load_memory(&mem);
+    emu.reset();
+    emu
+}
+
+#[test]
+fn batch_runs_leave_the_debug_log_alone() {
+    let mut stepped = unbalanced_return_program();
+    let mut batched = unbalanced_return_program();
+    let logged = batched.get_debug_logs().clone();
+
+    for _ in 0..5 {
+        stepped.step().unwrap();
+    }
+    stepped.set_irq_line(true);
+    stepped.step().unwrap();
+    assert_eq!(stepped.get_debug_logs().len(), logged.len() + 2);
+
+    batched.run_instructions(5).unwrap();
+    batched.set_irq_line(true);
+    batched.run_instructions(1).unwrap();
+    assert_eq!(batched.reg_pc().get(), stepped.reg_pc().get());
+    assert_eq!(batched.get_debug_logs(), &logged);
+}
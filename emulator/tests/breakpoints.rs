@@ -0,0 +1,70 @@
+mod common;
+use common::load_program;
+
+use emulator::breakpoint::{CompareOp, Condition, ConditionRegister, Operand, StopReason};
+
+// $20: INCA
+// $21: BRA $20
+const COUNT_LOOP: &[u8] = &[0x07, 0x21, 0xfd];
+
+#[test]
+fn stops_at_unconditional_breakpoint_before_executing_it() {
+    let mut emu = load_program(0x20, COUNT_LOOP);
+    emu.breakpoints_mut().add(0x21);
+
+    assert_eq!(emu.run_until_break(100), StopReason::Breakpoint(0x21));
+    assert_eq!(emu.reg_pc().get(), 0x21);
+    assert_eq!(emu.reg_a().get(), 1);
+
+    assert_eq!(emu.run_until_break(100), StopReason::Breakpoint(0x21));
+    assert_eq!(emu.reg_a().get(), 2);
+    assert_eq!(emu.breakpoints().get(0x21).unwrap().hit_count(), 2);
+}
+
+#[test]
+fn conditional_breakpoint_waits_for_condition() {
+    let mut emu = load_program(0x20, COUNT_LOOP);
+    let condition = Condition::parse("PC == $20 && A > $3").unwrap();
+    emu.breakpoints_mut().add_conditional(0x20, condition);
+
+    assert_eq!(emu.run_until_break(100), StopReason::Breakpoint(0x20));
+    assert_eq!(emu.reg_a().get(), 4);
+}
+
+#[test]
+fn disabled_and_ignored_breakpoints_do_not_stop() {
+    let mut emu = load_program(0x20, COUNT_LOOP);
+    emu.breakpoints_mut().add(0x21).set_ignore_count(2);
+
+    assert_eq!(emu.run_until_break(100), StopReason::Breakpoint(0x21));
+    assert_eq!(emu.reg_a().get(), 3);
+
+    emu.breakpoints_mut().disable(0x21);
+    assert_eq!(emu.run_until_break(10), StopReason::StepLimit);
+}
+
+#[test]
+fn parses_memory_flags_and_grouping() {
+    let emu = load_program(0x20, COUNT_LOOP);
+
+    assert!(Condition::parse("M($20) == 7").unwrap().evaluate(&emu));
+    assert!(
+        Condition::parse("!(Z || C) && M(PC) != %0")
+            .unwrap()
+            .evaluate(&emu)
+    );
+
+    let value = Condition::parse("(M($20)) == 7 && ((PC) != 0)").unwrap();
+    assert!(value.evaluate(&emu));
+    assert_eq!(
+        Condition::parse("(A) == 3"),
+        Ok(Condition::Compare(
+            Operand::Register(ConditionRegister::A),
+            CompareOp::Eq,
+            Operand::Number(3)
+        ))
+    );
+
+    let error = Condition::parse("A >").unwrap_err();
+    assert_eq!(error.position, 3);
+}
@@ -0,0 +1,89 @@
+mod common;
+use common::load_program;
+
+use emulator::bus::{BusError, Device};
+
+/// Stores the last byte written and counts CPU reads
+#[derive(Default)]
+struct Latch {
+    value: u8,
+    last_offset: u8,
+    reads: u32,
+}
+
+impl Device for Latch {
+    fn name(&self) -> &str {
+        "latch"
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        self.value.wrapping_add(offset)
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        self.reads += 1;
+        self.peek(offset)
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        self.last_offset = offset;
+        self.value = value;
+    }
+
+    fn reset(&mut self) {
+        self.value = 0;
+    }
+}
+
+#[test]
+fn cpu_accesses_are_routed_to_the_mapped_device() {
+    // $20: LDA #$42
+    // $22: STA $f1
+    // $24: LDA $f0
+    let mut emu = load_program(0x20, &[0xf0, 0x42, 0xe1, 0xf1, 0xf1, 0xf0]);
+    let id = emu
+        .attach_device(0xf0..=0xf1, Box::new(Latch::default()))
+        .unwrap();
+
+    emu.step().unwrap();
+    emu.step().unwrap();
+    let latch = emu.bus().device_as::<Latch>(id).unwrap();
+    assert_eq!(latch.value, 0x42);
+    assert_eq!(latch.last_offset, 1);
+    assert_eq!(
+        emu.memory()[0xf1].get(),
+        0,
+        "RAM behind a device is untouched"
+    );
+    assert_eq!(emu.memory_at(0xf1_u8), 0x43);
+
+    emu.step().unwrap();
+    assert_eq!(emu.reg_a().get(), 0x42);
+    assert_eq!(emu.bus().device_as::<Latch>(id).unwrap().reads, 1);
+
+    emu.reset();
+    assert_eq!(emu.memory_at(0xf0_u8), 0);
+}
+
+#[test]
+fn overlapping_ranges_are_rejected() {
+    let mut emu = load_program(0x20, &[]);
+    let first = emu
+        .attach_device(0xf0..=0xf3, Box::new(Latch::default()))
+        .unwrap();
+
+    let Err(BusError::Overlap { existing, range }) =
+        emu.attach_device(0xf3..=0xf4, Box::new(Latch::default()))
+    else {
+        panic!("expected an overlap error");
+    };
+    assert_eq!(existing, first);
+    assert_eq!(range, 0xf0..=0xf3);
+
+    emu.bus_mut().detach(first).unwrap();
+    assert!(emu.bus().device_at(0xf3).is_none());
+    assert!(
+        emu.attach_device(0xf3..=0xf4, Box::new(Latch::default()))
+            .is_ok()
+    );
+}
@@ -0,0 +1,134 @@
+mod common;
+use common::load_program;
+
+use emulator::{breakpoint::StopReason, call_stack::CallFrame};
+
+// $20: LDSP #$e0
+// $22: JSR $40
+// $24: BSR $40
+// $26: NOP
+// $40: INCA
+// $41: RTS
+fn calls() -> emulator::Emulator {
+    let mut program = vec![0; 0x22];
+    program[..7].copy_from_slice(&[0x92, 0xe0, 0x34, 0x40, 0x20, 0x1a, 0x00]);
+    program[0x20..].copy_from_slice(&[0x07, 0x43]);
+    load_program(0x20, &program)
+}
+
+#[test]
+fn calls_push_frames_and_rts_pops_them() {
+    let mut emu = calls();
+    emu.step().unwrap();
+    emu.step().unwrap();
+
+    assert_eq!(emu.reg_pc().get(), 0x40);
+    assert_eq!(emu.memory_at(0xdf_u8), 0x24);
+    assert_eq!(
+        emu.call_stack(),
+        [CallFrame {
+            call_site: 0x22,
+            target: 0x40,
+            return_address: 0x24,
+            sp: 0xdf,
+        }]
+    );
+
+    emu.step().unwrap();
+    emu.step().unwrap();
+    assert_eq!(emu.reg_pc().get(), 0x24);
+    assert!(emu.call_stack().is_empty());
+
+    emu.step().unwrap();
+    assert_eq!(emu.reg_pc().get(), 0x40);
+    assert_eq!(emu.call_stack()[0].return_address, 0x26);
+
+    emu.step().unwrap();
+    emu.step().unwrap();
+    assert_eq!(emu.reg_pc().get(), 0x26);
+    assert_eq!(emu.reg_a().get(), 2);
+}
+
+#[test]
+fn step_over_and_step_out_run_whole_subroutines() {
+    let mut emu = calls();
+    emu.step().unwrap();
+
+    assert_eq!(emu.step_over(100), StopReason::Completed);
+    assert_eq!(emu.reg_pc().get(), 0x24);
+    assert_eq!(emu.reg_a().get(), 1);
+
+    emu.step().unwrap();
+    assert_eq!(emu.step_out(100), StopReason::Completed);
+    assert_eq!(emu.reg_pc().get(), 0x26);
+    assert_eq!(emu.reg_a().get(), 2);
+
+    assert_eq!(emu.step_out(100), StopReason::Completed);
+    assert_eq!(emu.reg_pc().get(), 0x26);
+    assert_eq!(emu.step_over(100), StopReason::Completed);
+    assert_eq!(emu.reg_pc().get(), 0x27);
+}
+
+#[test]
+fn step_over_stops_at_breakpoints_inside_the_subroutine() {
+    let mut emu = calls();
+    emu.step().unwrap();
+    emu.breakpoints_mut().toggle(0x41);
+
+    assert_eq!(emu.step_over(100), StopReason::Breakpoint(0x41));
+    assert_eq!(emu.call_stack().len(), 1);
+}
+
+#[test]
+fn unbalanced_stack_is_reported_on_return() {
+    // $20: LDSP #$e0
+    // $22: LDA #$30
+    // $24: JSR $40
+    // $40: PSHA
+    // $41: RTS
+    let mut program = vec![0; 0x22];
+    program[..6].copy_from_slice(&[0x92, 0xe0, 0xf0, 0x30, 0x34, 0x40]);
+    program[0x20..].copy_from_slice(&[0x10, 0x43]);
+    let mut emu = load_program(0x20, &program);
+
+    for _ in 0..5 {
+        emu.step().unwrap();
+    }
+
+    assert_eq!(emu.reg_pc().get(), 0x30);
+    assert!(emu.call_stack().is_empty());
+    assert_eq!(
+        emu.get_debug_logs().back().unwrap(),
+        "RTS at 41 returned to 30, expected 26 for call at 24"
+    );
+}
+
+#[test]
+fn step_back_restores_the_call_stack() {
+    let mut emu = calls();
+    for _ in 0..4 {
+        emu.step().unwrap();
+    }
+    assert!(emu.call_stack().is_empty());
+
+    emu.step_back();
+    assert_eq!(emu.call_stack().len(), 1);
+    assert_eq!(emu.reg_pc().get(), 0x41);
+
+    emu.step_back();
+    emu.step_back();
+    assert!(emu.call_stack().is_empty());
+    assert_eq!(emu.reg_pc().get(), 0x22);
+}
+
+#[test]
+fn jumps_with_an_operand_land_on_their_target() {
+    // $20: LDX #$30
+    // $22: JMP $10,X
+    let mut emu = load_program(0x20, &[0x90, 0x30, 0x53, 0x10]);
+    emu.step().unwrap();
+    emu.step().unwrap();
+
+    assert_eq!(emu.reg_pc().get(), 0x40);
+    assert!(emu.call_stack().is_empty());
+}
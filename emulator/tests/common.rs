@@ -0,0 +1,16 @@
+use emulator::Emulator;
+
+/// Builds an emulator with `program` placed at `origin`, the reset vector pointing at it,
+/// and the reset already performed.
+pub fn load_program(origin: u8, program: &[u8]) -> Emulator {
+    let mut mem = [0_u8; 256];
+    for (i, byte) in program.iter().enumerate() {
+        mem[origin as usize + i] = *byte;
+    }
+    mem[0xff] = origin;
+
+    let mut emu = Emulator::default();
+    emu.load_memory(&mem);
+    emu.reset();
+    emu
+}
@@ -0,0 +1,178 @@
+mod common;
+use common::load_program;
+
+use emulator::{
+    devices::{
+        Console, DigitCountError, Keypad, LedChange, Leds, MAX_DIGITS, SevenSegment, Switches,
+        decode_segments,
+    },
+    run::RunUntil,
+};
+
+// $20: LDA $fb
+// $22: STA $fc
+// $24: BRA $20
+const COPY_SWITCHES: &[u8] = &[0xf1, 0xfb, 0xe1, 0xfc, 0x21, 0xfa];
+
+#[test]
+fn program_copies_switches_to_leds() {
+    let mut emu = load_program(0x20, COPY_SWITCHES);
+    let switches = emu
+        .attach_device(0xfb..=0xfb, Box::new(Switches::new(0x81)))
+        .unwrap();
+    let leds = emu
+        .attach_device(0xfc..=0xfc, Box::new(Leds::default()))
+        .unwrap();
+
+    // The LEDs were attached before the first clock, so their ticks match clk_count
+    emu.step().unwrap();
+    let first_write = emu.clk_count() as u64;
+    emu.run_instructions(2).unwrap();
+    assert_eq!(emu.memory_at(0xfc_u8), 0x81);
+
+    let bank = emu.bus_mut().device_as_mut::<Switches>(switches).unwrap();
+    bank.set_switch(0, false);
+    bank.set_switch(3, true);
+    assert_eq!(bank.value(), 0x88);
+    emu.step().unwrap();
+    let second_write = emu.clk_count() as u64;
+    emu.run_instructions(5).unwrap();
+
+    let output = emu.bus().device_as::<Leds>(leds).unwrap();
+    assert!(output.is_on(3) && !output.is_on(0));
+    assert_eq!(
+        output.changes(),
+        [
+            LedChange {
+                ticks: first_write,
+                value: 0x81
+            },
+            LedChange {
+                ticks: second_write,
+                value: 0x88
+            },
+        ],
+        "unchanged writes are not recorded"
+    );
+}
+
+#[test]
+fn reset_turns_leds_off_and_keeps_switches() {
+    let mut emu = load_program(0x20, COPY_SWITCHES);
+    let switches = emu
+        .attach_device(0xfb..=0xfb, Box::new(Switches::new(0x0f)))
+        .unwrap();
+    let leds = emu
+        .attach_device(0xfc..=0xfc, Box::new(Leds::default()))
+        .unwrap();
+    emu.run_instructions(2).unwrap();
+
+    emu.reset();
+    let output = emu.bus().device_as::<Leds>(leds).unwrap();
+    assert_eq!(output.value(), 0);
+    assert!(output.changes().is_empty());
+    assert_eq!(
+        emu.bus().device_as::<Switches>(switches).unwrap().value(),
+        0x0f
+    );
+}
+
+#[test]
+fn keypad_latches_a_press_until_the_program_reads_it() {
+    // $20: LDA $f1
+    // $22: BPL $20
+    // $24: LDA $f0
+    // $26: STA $50
+    // $28: BRA $20
+    let mut emu = load_program(
+        0x20,
+        &[0xf1, 0xf1, 0x23, 0xfc, 0xf1, 0xf0, 0xe1, 0x50, 0x21, 0xf6],
+    );
+    let keypad = emu
+        .attach_device(0xf0..=0xf1, Box::new(Keypad::default()))
+        .unwrap();
+    emu.run_instructions(10).unwrap();
+    assert_eq!(emu.reg_pc().get(), 0x20);
+
+    let pad = emu.bus_mut().device_as_mut::<Keypad>(keypad).unwrap();
+    assert!(pad.press(0x0b));
+    assert!(
+        !pad.press(0x03),
+        "a second key is lost while one is latched"
+    );
+    assert_eq!(emu.memory_at(0xf1_u8), 0x80);
+
+    let until = RunUntil {
+        pc: Some(0x28),
+        ..RunUntil::default()
+    };
+    emu.run_until(&until);
+    assert_eq!(emu.memory_at(0x50_u8), 0x0b);
+    let pad = emu.bus().device_as::<Keypad>(keypad).unwrap();
+    assert_eq!(pad.pending(), None);
+    assert_eq!(emu.memory_at(0xf1_u8), 0);
+}
+
+#[test]
+fn seven_segment_digits_keep_their_pattern_when_deselected() {
+    // $20: LDA #$01
+    // $22: STA $e9
+    // $24: LDA #$06
+    // $26: STA $e8
+    // $28: LDA #$02
+    // $2a: STA $e9
+    // $2c: LDA #$db
+    // $2e: STA $e8
+    let mut emu = load_program(
+        0x20,
+        &[
+            0xf0, 0x01, 0xe1, 0xe9, 0xf0, 0x06, 0xe1, 0xe8, 0xf0, 0x02, 0xe1, 0xe9, 0xf0, 0xdb,
+            0xe1, 0xe8,
+        ],
+    );
+    let display = emu
+        .attach_device(0xe8..=0xe9, Box::new(SevenSegment::default()))
+        .unwrap();
+    emu.run_instructions(8).unwrap();
+
+    let display = emu.bus().device_as::<SevenSegment>(display).unwrap();
+    assert_eq!(display.digits(), [0x06, 0xdb, 0x00, 0x00]);
+    assert_eq!(display.text(), "12.  ");
+    assert_eq!(decode_segments(0x71), Some('F'));
+    assert_eq!(decode_segments(0x01), None);
+}
+
+#[test]
+fn seven_segment_rejects_unsupported_digit_counts() {
+    assert_eq!(SevenSegment::new(0), Err(DigitCountError(0)));
+    assert_eq!(SevenSegment::new(9), Err(DigitCountError(9)));
+    assert_eq!(SevenSegment::new(MAX_DIGITS).unwrap().digits().len(), 8);
+}
+
+#[test]
+fn console_echoes_input_to_output() {
+    // $20: LDA $f1
+    // $22: BPL $20
+    // $24: LDA $f0
+    // $26: STA $f0
+    // $28: BRA $20
+    let mut emu = load_program(
+        0x20,
+        &[0xf1, 0xf1, 0x23, 0xfc, 0xf1, 0xf0, 0xe1, 0xf0, 0x21, 0xf6],
+    );
+    let console = emu
+        .attach_device(0xf0..=0xf1, Box::new(Console::default()))
+        .unwrap();
+    emu.run_instructions(10).unwrap();
+    assert_eq!(emu.memory_at(0xf1_u8), 0x40, "ready for output, no input");
+
+    let terminal = emu.bus_mut().device_as_mut::<Console>(console).unwrap();
+    terminal.send_input(b"hi\n");
+    assert_eq!(emu.memory_at(0xf1_u8), 0xc0);
+    emu.run_instructions(30).unwrap();
+
+    let terminal = emu.bus_mut().device_as_mut::<Console>(console).unwrap();
+    assert!(!terminal.has_input());
+    assert_eq!(terminal.take_output(), b"hi\n");
+    assert!(terminal.output().is_empty());
+}
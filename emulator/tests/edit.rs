@@ -0,0 +1,105 @@
+mod common;
+use common::load_program;
+
+use emulator::{
+    CCFlag, QState,
+    edit::{Edit, EditRecord},
+    register::{InternalRegister, VisibleRegister},
+};
+
+#[test]
+fn edits_change_state_without_entering_history() {
+    // $20: STA 0,X
+    let mut emu = load_program(0x20, &[0xe3, 0x00]);
+    emu.set_register(VisibleRegister::A, 0x2a);
+    emu.set_register(VisibleRegister::X, 0x50);
+    emu.set_flag(CCFlag::C, true);
+    emu.write_memory_range(0x50, &[0x01, 0x02]);
+
+    assert_eq!(emu.history_len(), 0);
+    assert!(emu.reg_cc().get(CCFlag::C));
+    assert_eq!(emu.memory_at(0x51_u8), 0x02);
+    assert_eq!(emu.edits().len(), 5);
+    assert_eq!(
+        emu.edits()[0],
+        EditRecord {
+            clk_count: emu.clk_count(),
+            edit: Edit::Register {
+                register: VisibleRegister::A,
+                old: 0,
+                new: 0x2a,
+            },
+        }
+    );
+
+    emu.step().unwrap();
+    assert_eq!(emu.memory_at(0x50_u8), 0x2a);
+
+    // Stepping back undoes the program's store but keeps the edits
+    assert!(emu.step_back());
+    assert_eq!(emu.memory_at(0x50_u8), 0x01);
+    assert_eq!(emu.reg_x().get(), 0x50);
+    assert!(!emu.step_back());
+}
+
+#[test]
+fn internal_registers_can_be_edited() {
+    // $20: LDA $40
+    let mut emu = load_program(0x20, &[0xf1, 0x40]);
+    for (register, value) in [
+        (InternalRegister::R, 0x11),
+        (InternalRegister::TA, 0x22),
+        (InternalRegister::LD, 0x33),
+        (InternalRegister::I, 0x44),
+    ] {
+        emu.set_internal_register(register, value);
+        assert_eq!(emu.internal_register(register), value);
+    }
+    assert_eq!(emu.reg_ta().get(), 0x22);
+    assert_eq!(emu.reg_i().get(), 0x44);
+    assert_eq!(
+        emu.edits()[0].edit,
+        Edit::InternalRegister {
+            register: InternalRegister::R,
+            old: 0,
+            new: 0x11,
+        }
+    );
+
+    emu.step().unwrap();
+    assert_eq!(emu.reg_i().get(), 0xf1);
+    assert_eq!(emu.reg_ta().get(), 0x40);
+    assert_eq!(emu.reg_r().get(), 0x11, "R is untouched by a load");
+}
+
+#[test]
+fn setting_pc_and_q_state_redirects_execution() {
+    // $20: INCA
+    // $21: INCA
+    // $22: DECA
+    let mut emu = load_program(0x20, &[0x07, 0x07, 0x08]);
+    emu.set_register(VisibleRegister::PC, 0x22);
+    emu.step().unwrap();
+    assert_eq!(emu.reg_a().get(), 0xff);
+
+    assert!(!emu.set_q_state(QState::Execute));
+    assert!(emu.set_q_state(QState::Reset));
+    emu.step().unwrap();
+    assert_eq!(emu.reg_pc().get(), 0x20);
+    assert_eq!(emu.q_state(), QState::Fetch);
+}
+
+#[test]
+fn editing_mid_instruction_abandons_it() {
+    // $20: INCA
+    let mut emu = load_program(0x20, &[0x07]);
+    emu.micro_step().unwrap();
+    assert!(emu.instruction_clock().is_some());
+
+    emu.set_register(VisibleRegister::A, 0x10);
+    assert_eq!(emu.instruction_clock(), None);
+    assert_eq!(emu.reg_pc().get(), 0x20);
+
+    emu.step().unwrap();
+    assert_eq!(emu.reg_a().get(), 0x11);
+}
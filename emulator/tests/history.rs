@@ -0,0 +1,112 @@
+mod common;
+use common::load_program;
+
+use emulator::Emulator;
+
+// $20: LDX #$40
+// $22: LDA #$01
+// $24: STA ,X+
+// $25: INCA
+// $26: BRA $24
+const FILL: &[u8] = &[0x90, 0x40, 0xf0, 0x01, 0xe5, 0x07, 0x21, 0xfc];
+
+#[test]
+fn step_back_restores_registers_memory_and_clock() {
+    let mut emu = load_program(0x20, FILL);
+    for _ in 0..3 {
+        emu.step().unwrap();
+    }
+    assert_eq!(emu.memory_at(0x40), 1);
+    assert_eq!(emu.reg_x().get(), 0x41);
+    let clk = emu.clk_count();
+
+    emu.step().unwrap();
+    emu.step_back();
+    assert_eq!(emu.clk_count(), clk);
+
+    assert!(emu.step_back());
+    assert_eq!(emu.memory_at(0x40), 0);
+    assert_eq!(emu.reg_x().get(), 0x40);
+    assert_eq!(emu.reg_pc().get(), 0x24);
+
+    assert!(emu.step_back());
+    assert!(emu.step_back());
+    assert!(!emu.step_back());
+    assert_eq!(emu.reg_pc().get(), 0x20);
+    assert_eq!(emu.clk_count(), 0);
+}
+
+#[test]
+fn step_back_restores_debugging_state() {
+    // $20: LDSP #$e0
+    // $22: LDA $40, never initialized
+    // $24: STA $41
+    // $26: PSHA
+    let mut mem = [0_u8; 256];
+    mem[0x20..0x27].copy_from_slice(&[0x92, 0xe0, 0xf1, 0x40, 0xe1, 0x41, 0x10]);
+    mem[0xff] = 0x20;
+    let mut initialized = [true; 256];
+    initialized[0x40] = false;
+    let mut emu = Emulator::default();
+    emu.load_image(&mem, &initialized);
+    emu.set_stack_region(0xc0..=0xdf);
+    emu.reset();
+
+    let state = |emu: &Emulator| {
+        (
+            emu.written_at_runtime(0x41),
+            emu.stack_high_water_mark(),
+            emu.uninitialized_reads().len(),
+        )
+    };
+    let mut states = Vec::new();
+    for _ in 0..4 {
+        states.push(state(&emu));
+        emu.step().unwrap();
+    }
+    assert_eq!(state(&emu), (true, Some(0xdf), 1));
+
+    while let Some(expected) = states.pop() {
+        assert!(emu.step_back());
+        assert_eq!(state(&emu), expected);
+    }
+    assert_eq!(state(&emu), (false, None, 0));
+}
+
+#[test]
+fn reverse_continue_stops_at_previous_breakpoint() {
+    let mut emu = load_program(0x20, FILL);
+    for _ in 0..20 {
+        emu.step().unwrap();
+    }
+    emu.breakpoints_mut().add(0x25);
+
+    assert_eq!(emu.reverse_continue(), Some(0x25));
+    assert_eq!(emu.reg_pc().get(), 0x25);
+    let a = emu.reg_a().get();
+    assert_eq!(emu.reverse_continue(), Some(0x25));
+    assert_eq!(emu.reg_a().get(), a - 1);
+
+    emu.breakpoints_mut().clear();
+    assert_eq!(emu.reverse_continue(), None);
+    assert_eq!(emu.reg_pc().get(), 0x20);
+}
+
+#[test]
+fn history_budget_drops_oldest_steps() {
+    let mut emu = load_program(0x20, FILL);
+    emu.set_history_budget(0);
+    emu.step().unwrap();
+    assert_eq!(emu.history_len(), 0);
+
+    emu.set_history_budget(512);
+    for _ in 0..100 {
+        emu.step().unwrap();
+    }
+    let len = emu.history_len();
+    assert!(len > 0 && len < 100);
+    for _ in 0..len {
+        assert!(emu.step_back());
+    }
+    assert!(!emu.step_back());
+}
@@ -0,0 +1,98 @@
+use emulator::{
+    CCFlag, Emulator,
+    interrupt::{IRQ_CYCLES, IRQ_VECTOR},
+    outcome::StepOutcome,
+};
+
+// $20: LDSP #$e0
+// $22: INCA
+// $23: INCA
+// $40: RTI
+fn interrupt_program() -> Emulator {
+    let mut mem = [0_u8; 256];
+    mem[0x20..0x24].copy_from_slice(&[0x92, 0xe0, 0x07, 0x07]);
+    mem[0x40] = 0x44;
+    mem[IRQ_VECTOR as usize] = 0x40;
+    mem[0xff] = 0x20;
+
+    let mut emu = Emulator::default();
+    emu.load_memory(&mem);
+    emu.reset();
+    emu
+}
+
+#[test]
+fn irq_pushes_state_and_rti_restores_it() {
+    let mut emu = interrupt_program();
+    emu.step().unwrap();
+    emu.step().unwrap();
+    let cc = emu.reg_cc().data();
+    let clk = emu.clk_count();
+
+    emu.set_irq_line(true);
+    assert_eq!(
+        emu.step(),
+        Ok(StepOutcome::Interrupt {
+            pc: 0x23,
+            handler: 0x40
+        })
+    );
+    assert_eq!(emu.reg_pc().get(), 0x40);
+    assert_eq!(emu.reg_sp().get(), 0xdb);
+    assert!(emu.reg_cc().get(CCFlag::I));
+    assert_eq!(emu.clk_count(), clk + IRQ_CYCLES);
+    // CC, A, X, Y, PC from the top of the stack, the order RTI pulls them
+    let stacked: Vec<_> = (0xdb..0xe0).map(|adr: u8| emu.memory_at(adr)).collect();
+    assert_eq!(stacked, [cc, 1, 0, 0, 0x23]);
+
+    // I is set, so the still-asserted line does not interrupt the handler
+    emu.step().unwrap();
+    assert_eq!(emu.reg_pc().get(), 0x23);
+    assert_eq!(emu.reg_sp().get(), 0xe0);
+    assert!(!emu.reg_cc().get(CCFlag::I));
+
+    emu.set_irq_line(false);
+    assert_eq!(emu.step(), Ok(StepOutcome::Executed));
+    assert_eq!(emu.reg_a().get(), 2);
+}
+
+#[test]
+fn interrupt_entry_can_be_stepped_back() {
+    let mut emu = interrupt_program();
+    emu.step().unwrap();
+    let before = emu.snapshot();
+
+    emu.set_irq_line(true);
+    emu.step().unwrap();
+    assert!(emu.step_back());
+    assert!(emu.snapshot() == before);
+}
+
+#[test]
+fn interrupt_entry_micro_steps_one_push_per_clock() {
+    let mut emu = interrupt_program();
+    emu.step().unwrap();
+    emu.step().unwrap();
+    let clk = emu.clk_count();
+
+    emu.set_irq_line(true);
+    for clock in 1..IRQ_CYCLES {
+        assert_eq!(emu.micro_step(), Ok(None));
+        assert_eq!(emu.last_accesses().len(), clock.min(6) as usize);
+    }
+    assert_eq!(
+        emu.reg_ld().get(),
+        0x40,
+        "vector fetched on the sixth clock"
+    );
+    assert_eq!(emu.reg_pc().get(), 0x23);
+    assert_eq!(
+        emu.micro_step(),
+        Ok(Some(StepOutcome::Interrupt {
+            pc: 0x23,
+            handler: 0x40
+        }))
+    );
+    assert_eq!(emu.clk_count(), clk + IRQ_CYCLES);
+    assert!(emu.reg_cc().get(CCFlag::I));
+}
@@ -0,0 +1,146 @@
+use emulator::{
+    Emulator,
+    devices::{Console, Switches, TIMER_ENABLE, TIMER_IRQ_ENABLE, Timer},
+    machine::{DeviceConfig, DeviceKind, Machine, MachineError},
+    protection::Protection,
+};
+
+const LAB_BOARD: &str = r#"
+# Variant used in the interrupt labs
+[memory]
+ram = ["40-ef"]
+rom = "00-1f"
+stack = "$80-df"
+
+[[device]]
+type = "switches"
+at = "fb"
+value = 0x0f
+
+[[device]]
+type = "timer"
+at = 0xf4
+irq = false
+
+[[device]]
+type = "console"
+at = "f0-f1"
+input = "go\n"
+"#;
+
+#[test]
+fn machine_description_is_parsed() {
+    let machine = Machine::parse(LAB_BOARD).unwrap();
+    assert_eq!(machine.memory.ram, [0x40..=0xef]);
+    assert_eq!(machine.memory.rom, [0x00..=0x1f]);
+    assert_eq!(machine.memory.stack, Some(0x80..=0xdf));
+    assert_eq!(
+        machine.devices,
+        [
+            DeviceConfig {
+                kind: DeviceKind::Switches { value: 0x0f },
+                range: 0xfb..=0xfb,
+                irq: true,
+            },
+            DeviceConfig {
+                kind: DeviceKind::Timer,
+                range: 0xf4..=0xf7,
+                irq: false,
+            },
+            DeviceConfig {
+                kind: DeviceKind::Console {
+                    input: b"go\n".to_vec()
+                },
+                range: 0xf0..=0xf1,
+                irq: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn emulator_is_wired_as_described() {
+    let mut emu = Emulator::with_machine(&Machine::parse(LAB_BOARD).unwrap()).unwrap();
+    assert_eq!(emu.memory_at(0xfb_u8), 0x0f);
+    assert_eq!(emu.protection_at(0x10), Some(Protection::Rom));
+    assert_eq!(emu.protection_at(0x30), Some(Protection::Rom), "not RAM");
+    assert_eq!(emu.protection_at(0x40), None);
+    assert_eq!(emu.protection_at(0xf0), None, "devices stay writable");
+    assert_eq!(emu.stack_region(), Some(0x80..=0xdf));
+
+    let devices: Vec<_> = emu.bus().devices().map(|(id, _, _)| id).collect();
+    assert!(emu.bus().device_as::<Switches>(devices[0]).is_some());
+    let console = emu.bus().device_as::<Console>(devices[2]).unwrap();
+    assert!(console.has_input());
+
+    let timer = devices[1];
+    assert_eq!(emu.bus().irq_connected(timer), Some(false));
+    emu.load_memory(&[0; 256]);
+    emu.reset();
+    emu.bus_mut().write(0xf5, 1);
+    emu.bus_mut().write(0xf4, TIMER_ENABLE | TIMER_IRQ_ENABLE);
+    emu.run_cycles(10).unwrap();
+    assert!(emu.bus().device_as::<Timer>(timer).unwrap().is_expired());
+    assert!(!emu.irq_pending(), "the timer IRQ is not connected");
+}
+
+#[test]
+fn explicit_ranges_are_taken_as_written() {
+    let machine = Machine::parse("[[device]]\ntype = \"seven-segment\"\nat = \"e0-e3\"").unwrap();
+    assert_eq!(machine.devices[0].range, 0xe0..=0xe3);
+
+    assert_eq!(
+        Machine::parse("[[device]]\ntype = \"keypad\"\nat = \"f0-f0\"")
+            .unwrap_err()
+            .to_string(),
+        "line 3: keypad needs 2 addresses"
+    );
+}
+
+#[test]
+fn mistakes_are_reported_with_their_line() {
+    let error = |src: &str| match Machine::parse(src) {
+        Err(MachineError::Invalid { line, message }) => (line.unwrap(), message),
+        other => panic!("expected an error, found {:?}", other),
+    };
+
+    assert_eq!(
+        error("[[device]]\ntype = \"lamp\"\nat = \"f0\""),
+        (
+            2,
+            "unknown variant `lamp`, expected one of `switches`, `leds`, `seven-segment`, \
+             `keypad`, `timer`, `console`"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        error("[memory]\nram = []\n\n[[device]]\ntype = \"leds\"\nat = \"f0\"\ndigts = 2").0,
+        7
+    );
+    assert_eq!(
+        error("[[device]]\ntype = \"leds\"\nat = \"f0\"\ndigits = 2"),
+        (4, "leds has no `digits` setting".to_string())
+    );
+    assert_eq!(
+        error(
+            "[[device]]\ntype = \"leds\"\nat = \"f1\"\n\n[[device]]\ntype = \"keypad\"\nat = \"f0\""
+        ),
+        (7, "device at f0-f1 overlaps device at f1-f1".to_string())
+    );
+    assert_eq!(
+        error("[[device]]\ntype = \"timer\"\nat = \"fe\""),
+        (3, "timer needs 4 addresses".to_string())
+    );
+    assert_eq!(
+        error("[[device]]\ntype = \"seven-segment\"\nat = \"e8\"\ndigits = 9"),
+        (4, "a display has 1 to 8 digits, not 9".to_string())
+    );
+    assert_eq!(error("[memory]\nrom = [\"00-1f\",\nstack = 1").0, 3);
+    assert_eq!(
+        error("[memory]\nrom = \"00-1f\"\nstak = \"80-df\""),
+        (
+            3,
+            "unknown field `stak`, expected one of `ram`, `rom`, `code`, `stack`".to_string()
+        )
+    );
+}
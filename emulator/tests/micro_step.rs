@@ -0,0 +1,196 @@
+mod common;
+use common::load_program;
+
+use emulator::{
+    QState,
+    access::AccessKind,
+    devices::Leds,
+    micro::ControlState,
+    outcome::{ExecutionError, StepOutcome},
+};
+
+// $20: LDA #$05
+// $22: STA $40
+// $24: INCA
+const STORE: &[u8] = &[0xf0, 0x05, 0xe1, 0x40, 0x07];
+
+#[test]
+fn store_is_visible_clock_by_clock() {
+    let mut emu = load_program(0x20, STORE);
+    emu.step().unwrap();
+    let clk = emu.clk_count();
+
+    // Fetch: I is loaded and PC moves past the opcode
+    assert_eq!(emu.micro_step(), Ok(None));
+    assert_eq!(emu.q_state(), QState::Execute);
+    assert_eq!(emu.control_state(), Some(ControlState::Fetch));
+    assert_eq!(emu.reg_i().get(), 0xe1);
+    assert_eq!(emu.reg_pc().get(), 0x23);
+    assert_eq!(emu.clk_count(), clk + 1);
+
+    // Operand fetch
+    assert_eq!(emu.micro_step(), Ok(None));
+    assert_eq!(
+        emu.control_state(),
+        Some(ControlState::Access(AccessKind::Fetch))
+    );
+    assert_eq!(emu.reg_ld().get(), 0x40);
+    assert_eq!(emu.reg_pc().get(), 0x24);
+    assert_eq!(emu.memory_at(0x40_u8), 0);
+
+    // Write
+    let (_, clocks) = emu.instruction_clock().unwrap();
+    let mut outcome = emu.micro_step().unwrap();
+    assert_eq!(emu.reg_ta().get(), 0x40);
+    assert_eq!(emu.reg_r().get(), 0x05);
+    assert_eq!(emu.memory_at(0x40_u8), 0x05);
+    while outcome.is_none() {
+        outcome = emu.micro_step().unwrap();
+    }
+
+    assert_eq!(outcome, Some(StepOutcome::Executed));
+    assert_eq!(emu.q_state(), QState::Fetch);
+    assert_eq!(emu.instruction_clock(), None);
+    assert_eq!(emu.reg_pc().get(), 0x24);
+    assert_eq!(emu.clk_count(), clk + clocks);
+}
+
+#[test]
+fn micro_stepping_matches_whole_instruction_steps() {
+    let mut whole = load_program(0x20, STORE);
+    let mut micro = load_program(0x20, STORE);
+
+    for _ in 0..3 {
+        whole.step().unwrap();
+        while micro.micro_step().unwrap().is_none() {}
+        assert!(whole.snapshot() == micro.snapshot());
+        assert_eq!(whole.reg_ld().get(), micro.reg_ld().get());
+    }
+}
+
+#[test]
+fn every_opcode_micro_steps_like_a_whole_step() {
+    for opcode in 0..=0xff_u8 {
+        // $20: LDSP #$c0
+        // $22: LDX #$30
+        // $24: the opcode with operand $40
+        let program = [0x92, 0xc0, 0x90, 0x30, opcode, 0x40];
+        let mut whole = load_program(0x20, &program);
+        let mut micro = load_program(0x20, &program);
+        for emu in [&mut whole, &mut micro] {
+            emu.step().unwrap();
+            emu.step().unwrap();
+        }
+
+        let expected = whole.step();
+        let mut accesses = 0;
+        let outcome = loop {
+            let outcome = micro.micro_step();
+            assert!(
+                micro.last_accesses().len() <= accesses + 1,
+                "{:02x} made more than one access on a clock",
+                opcode
+            );
+            accesses = micro.last_accesses().len();
+            match outcome {
+                Ok(None) => {}
+                Ok(Some(outcome)) => break Ok(outcome),
+                Err(error) => break Err(error),
+            }
+        };
+
+        assert_eq!(outcome, expected, "{:02x}", opcode);
+        assert_eq!(micro.clk_count(), whole.clk_count(), "{:02x}", opcode);
+        assert_eq!(
+            micro.last_accesses(),
+            whole.last_accesses(),
+            "{:02x}",
+            opcode
+        );
+        assert!(micro.snapshot() == whole.snapshot(), "{:02x}", opcode);
+    }
+}
+
+#[test]
+fn device_sees_a_store_on_its_write_clock() {
+    // $20: LDA #$05
+    // $22: STA $f0
+    let mut emu = load_program(0x20, &[0xf0, 0x05, 0xe1, 0xf0]);
+    let id = emu
+        .attach_device(0xf0..=0xf0, Box::new(Leds::default()))
+        .unwrap();
+    let leds = |emu: &emulator::Emulator| emu.bus().device_as::<Leds>(id).unwrap().value();
+    emu.step().unwrap();
+
+    emu.micro_step().unwrap();
+    emu.micro_step().unwrap();
+    assert_eq!(leds(&emu), 0, "not on the fetch or operand clocks");
+    assert_eq!(emu.history_len(), 1);
+    assert_eq!(emu.micro_step(), Ok(Some(StepOutcome::Executed)));
+    assert_eq!(leds(&emu), 0x05);
+    assert_eq!(emu.history_len(), 2, "recorded on the last clock");
+}
+
+#[test]
+fn push_writes_on_its_second_clock_and_finishes_internally() {
+    // $20: LDSP #$e0
+    // $22: LDA #$05
+    // $24: PSHA
+    let mut emu = load_program(0x20, &[0x92, 0xe0, 0xf0, 0x05, 0x10]);
+    emu.step().unwrap();
+    emu.step().unwrap();
+
+    emu.micro_step().unwrap();
+    assert_eq!(emu.micro_step(), Ok(None));
+    assert_eq!(
+        emu.control_state(),
+        Some(ControlState::Access(AccessKind::Write))
+    );
+    assert_eq!(emu.reg_sp().get(), 0xdf);
+    assert_eq!(emu.reg_r().get(), 0x05);
+    assert_eq!(emu.memory_at(0xdf_u8), 0x05);
+
+    assert_eq!(emu.micro_step(), Ok(Some(StepOutcome::Executed)));
+    assert_eq!(emu.control_state(), None);
+}
+
+#[test]
+fn invalid_opcode_faults_on_the_fetch_clock() {
+    let mut emu = load_program(0x20, &[0x03]);
+    assert_eq!(
+        emu.micro_step(),
+        Err(ExecutionError::InvalidOpcode {
+            pc: 0x20,
+            opcode: 0x03,
+        })
+    );
+    assert_eq!(emu.instruction_clock(), None);
+}
+
+#[test]
+fn step_back_mid_instruction_returns_to_its_start() {
+    // $20: LDSP #$e0
+    // $22: LDA #$05
+    // $24: PSHA
+    let mut emu = load_program(0x20, &[0x92, 0xe0, 0xf0, 0x05, 0x10]);
+    emu.step().unwrap();
+    emu.step().unwrap();
+    let start = emu.snapshot();
+
+    emu.micro_step().unwrap();
+    emu.micro_step().unwrap();
+    assert_eq!(emu.memory_at(0xdf_u8), 0x05);
+    assert!(
+        emu.snapshot() == start,
+        "snapshots are taken at instruction boundaries"
+    );
+
+    assert!(emu.step_back());
+    assert!(emu.snapshot() == start);
+    assert_eq!(emu.memory_at(0xdf_u8), 0);
+    assert_eq!(emu.q_state(), QState::Fetch);
+
+    emu.micro_step().unwrap();
+    assert_eq!(emu.step(), Ok(StepOutcome::Executed));
+    assert_eq!(emu.reg_sp().get(), 0xdf);
+}
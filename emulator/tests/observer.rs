@@ -0,0 +1,100 @@
+mod common;
+use common::load_program;
+
+use emulator::{
+    Emulator, observer::EmulatorObserver, register::VisibleRegister, trace::TraceRegisters,
+};
+
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+}
+
+impl EmulatorObserver for Recorder {
+    fn on_fetch(&mut self, address: u8, value: u8) {
+        self.events
+            .push(format!("fetch {:02x}={:02x}", address, value));
+    }
+
+    fn on_memory_write(&mut self, address: u8, value: u8, previous: u8) {
+        self.events.push(format!(
+            "write {:02x}={:02x} was {:02x}",
+            address, value, previous
+        ));
+    }
+
+    fn on_register_change(&mut self, register: VisibleRegister, old: u8, new: u8) {
+        self.events
+            .push(format!("{:?} {:02x}->{:02x}", register, old, new));
+    }
+
+    fn on_instruction_executed(
+        &mut self,
+        pc: u8,
+        opcode: u8,
+        _before: &TraceRegisters,
+        _after: &TraceRegisters,
+    ) {
+        self.events
+            .push(format!("executed {:02x} at {:02x}", opcode, pc));
+    }
+
+    fn on_reset(&mut self, pc: u8) {
+        self.events.push(format!("reset to {:02x}", pc));
+    }
+
+    fn on_debug_log(&mut self, msg: &str) {
+        self.events.push(format!("log {}", msg));
+    }
+}
+
+#[test]
+fn observers_see_accesses_register_changes_and_instructions() {
+    // $20: LDA #$05
+    // $22: STA $50
+    let mut emu = load_program(0x20, &[0xf0, 0x05, 0xe1, 0x50]);
+    let id = emu.add_observer(Box::<Recorder>::default());
+
+    emu.step().unwrap();
+    emu.step().unwrap();
+
+    let recorder = emu.observer_as::<Recorder>(id).unwrap();
+    assert_eq!(
+        recorder.events,
+        [
+            "fetch 20=f0",
+            "fetch 21=05",
+            "A 00->05",
+            "PC 20->22",
+            "executed f0 at 20",
+            "fetch 22=e1",
+            "fetch 23=50",
+            "write 50=05 was 00",
+            "PC 22->24",
+            "executed e1 at 22",
+        ]
+    );
+}
+
+#[test]
+fn every_registered_observer_is_notified_until_removed() {
+    let mut emu = Emulator::default();
+    let first = emu.add_observer(Box::<Recorder>::default());
+    let second = emu.add_observer(Box::<Recorder>::default());
+    let mut mem = [0; 256];
+    mem[0xff] = 0x30;
+    emu.load_memory(&mem);
+    emu.reset();
+
+    let removed = emu.remove_observer(first).unwrap();
+    emu.debug_log("hello".to_string());
+
+    let first_events = &(removed as Box<dyn std::any::Any>)
+        .downcast::<Recorder>()
+        .unwrap()
+        .events;
+    assert_eq!(first_events.last().unwrap(), "reset to 30");
+    let second_events = &emu.observer_as::<Recorder>(second).unwrap().events;
+    assert_eq!(second_events.last().unwrap(), "log hello");
+    assert!(emu.observer_as::<Recorder>(first).is_none());
+}
@@ -0,0 +1,87 @@
+mod common;
+use common::load_program;
+
+use std::collections::BTreeMap;
+
+use flisp_core::opcode;
+
+// $20: LDSP #$e0
+// $22: LDX #$40
+// $24: CLRA
+// $25: JSR A,X
+// $26: NOP
+// $40: NOP
+// $41: RTS
+fn call_program() -> emulator::Emulator {
+    let mut program = vec![0; 0x22];
+    program[..7].copy_from_slice(&[0x92, 0xe0, 0x90, 0x40, 0x05, 0x64, 0x00]);
+    program[0x20..].copy_from_slice(&[0x00, 0x43]);
+    load_program(0x20, &program)
+}
+
+fn clocks(op: u8) -> u64 {
+    opcode::info(op).unwrap().clocks as u64
+}
+
+#[test]
+fn counts_executions_and_cycles_per_address() {
+    // $20: INCA
+    // $21: BRA $20
+    let mut emu = load_program(0x20, &[0x07, 0x21, 0xfd]);
+    emu.start_profiling();
+    for _ in 0..6 {
+        emu.step().unwrap();
+    }
+
+    let profile = emu.stop_profiling().unwrap();
+    assert_eq!(profile.at(0x20).executions, 3);
+    assert_eq!(profile.at(0x20).cycles, 3 * clocks(0x07));
+    assert_eq!(
+        profile.executed_addresses().collect::<Vec<_>>(),
+        [0x20, 0x21]
+    );
+    assert!(!profile.executed(0x22));
+    assert_eq!(profile.total_cycles(), 3 * (clocks(0x07) + clocks(0x21)));
+    assert_eq!(profile.by_mnemonic()["BRA"].executions, 3);
+
+    let hottest = profile.hot_spots(1)[0].0;
+    let busier = if clocks(0x07) >= clocks(0x21) {
+        0x20
+    } else {
+        0x21
+    };
+    assert_eq!(hottest, busier);
+}
+
+#[test]
+fn attributes_cycles_to_labels_and_subroutines() {
+    let mut emu = call_program();
+    emu.start_profiling();
+    for _ in 0..7 {
+        emu.step().unwrap();
+    }
+    assert_eq!(emu.reg_pc().get(), 0x27);
+
+    let profile = emu.profile().unwrap();
+    let subroutine = profile.subroutines()[&0x40];
+    assert_eq!(subroutine.executions, 1);
+    assert_eq!(
+        subroutine.cycles,
+        clocks(0x64) + clocks(0x00) + clocks(0x43)
+    );
+
+    let labels = BTreeMap::from([("main".to_string(), 0x20), ("sub".to_string(), 0x40)]);
+    let by_label = profile.by_label(&labels);
+    assert_eq!(by_label["sub"].cycles, clocks(0x00) + clocks(0x43));
+    assert_eq!(by_label["main"].executions, 5);
+
+    let mut report = Vec::new();
+    profile.write_report(&mut report, Some(&labels)).unwrap();
+    let report = String::from_utf8(report).unwrap();
+    assert!(report.contains("Subroutines"));
+    assert!(
+        report
+            .lines()
+            .any(|line| line.trim_start().starts_with("40 sub"))
+    );
+}
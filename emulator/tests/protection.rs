@@ -0,0 +1,95 @@
+mod common;
+use common::load_program;
+
+use emulator::{
+    access::AccessKind,
+    outcome::{ExecutionError, StepOutcome},
+    protection::Protection,
+    watchpoint::WatchKind,
+};
+
+#[test]
+fn writes_to_rom_are_dropped_and_fault() {
+    // $20: LDA #$07
+    // $22: STA $50
+    // $24: NOP
+    let mut emu = load_program(0x20, &[0xf0, 0x07, 0xe1, 0x50, 0x00]);
+    emu.protect(0x50..=0x5f, Protection::Rom);
+
+    emu.step().unwrap();
+    assert_eq!(
+        emu.step(),
+        Err(ExecutionError::WriteProtected {
+            pc: 0x22,
+            address: 0x50,
+        })
+    );
+    assert_eq!(emu.memory_at(0x50_u8), 0);
+    assert_eq!(emu.reg_pc().get(), 0x24);
+
+    emu.step().unwrap();
+    assert_eq!(emu.reg_pc().get(), 0x25);
+}
+
+#[test]
+fn rom_fault_still_reports_watchpoints_and_breakpoints() {
+    // $20: STA $60
+    // $22: STA $50
+    // $24: NOP
+    let mut emu = load_program(0x20, &[0xe1, 0x60, 0xe1, 0x50, 0x00]);
+    emu.protect(0x50..=0x5f, Protection::Rom);
+    emu.watchpoints_mut().add_address(0x60, WatchKind::Write);
+    emu.breakpoints_mut().add(0x24);
+
+    assert!(matches!(emu.step(), Ok(StepOutcome::WatchpointHit(_))));
+    assert_eq!(emu.watchpoint_hits().len(), 1);
+
+    assert_eq!(
+        emu.step(),
+        Err(ExecutionError::WriteProtected {
+            pc: 0x22,
+            address: 0x50,
+        })
+    );
+    assert!(emu.watchpoint_hits().is_empty());
+    assert_eq!(emu.breakpoints().get(0x24).unwrap().hit_count(), 1);
+
+    // The dropped write is neither recorded nor marked as written
+    assert!(
+        emu.last_accesses()
+            .iter()
+            .all(|access| access.kind != AccessKind::Write)
+    );
+    assert!(!emu.written_at_runtime(0x50));
+    assert!(emu.written_at_runtime(0x60));
+}
+
+#[test]
+fn overwriting_and_executing_code_is_reported() {
+    // $20: LDA #$07
+    // $22: STA $24
+    // $24: NOP, replaced by INCA
+    let mut emu = load_program(0x20, &[0xf0, 0x07, 0xe1, 0x24, 0x00]);
+    let mut program = [false; 256];
+    program[0x20..=0x24].fill(true);
+    emu.protect_bytes(&program, Protection::Code);
+
+    emu.step().unwrap();
+    emu.step().unwrap();
+    assert!(emu.written_at_runtime(0x24));
+    assert_eq!(
+        emu.get_debug_logs().back().unwrap(),
+        "Write to code at 24 by instruction at 22"
+    );
+
+    emu.step().unwrap();
+    assert_eq!(emu.reg_a().get(), 0x08);
+    assert_eq!(
+        emu.get_debug_logs().back().unwrap(),
+        "Executing 24, written at runtime, in instruction at 24"
+    );
+
+    emu.reset();
+    assert!(!emu.written_at_runtime(0x24));
+    assert_eq!(emu.protection_at(0x24), Some(Protection::Code));
+}
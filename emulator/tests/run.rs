@@ -0,0 +1,116 @@
+mod common;
+use common::load_program;
+
+use emulator::{
+    breakpoint::StopReason,
+    devices::{Console, TIMER_ENABLE, TIMER_IRQ_ENABLE, Timer},
+    run::RunUntil,
+};
+
+// $20: LDA #$0a
+// $22: STA $50
+// $24: DECA
+// $25: BNE $22
+// $27: BRA $27
+const COUNTDOWN: &[u8] = &[0xf0, 0x0a, 0xe1, 0x50, 0x08, 0x25, 0xfb, 0x21, 0xfe];
+
+#[test]
+fn limits_and_target_pc_stop_the_run() {
+    let mut emu = load_program(0x20, COUNTDOWN);
+    let until = RunUntil {
+        max_instructions: Some(4),
+        ..RunUntil::default()
+    };
+    assert_eq!(emu.run_until(&until), StopReason::StepLimit);
+    assert_eq!(emu.reg_pc().get(), 0x22);
+
+    let start = emu.clk_count();
+    let until = RunUntil {
+        max_cycles: Some(20),
+        ..RunUntil::default()
+    };
+    assert_eq!(emu.run_until(&until), StopReason::CycleLimit);
+    assert!(emu.clk_count() - start >= 20);
+
+    let until = RunUntil {
+        pc: Some(0x27),
+        ..RunUntil::default()
+    };
+    assert_eq!(emu.run_until(&until), StopReason::ReachedPc(0x27));
+    assert_eq!(emu.reg_a().get(), 0);
+}
+
+#[test]
+fn branch_to_itself_is_detected() {
+    let mut emu = load_program(0x20, COUNTDOWN);
+    let until = RunUntil {
+        detect_halt: true,
+        ..RunUntil::default()
+    };
+
+    assert_eq!(emu.run_until(&until), StopReason::SelfBranch(0x27));
+    assert_eq!(emu.memory_at(0x50_u8), 0x01);
+}
+
+#[test]
+fn halt_is_not_reported_while_a_device_may_interrupt() {
+    let mut emu = load_program(0x20, COUNTDOWN);
+    emu.attach_device(0xf0..=0xf1, Box::new(Console::default()))
+        .unwrap();
+    let timer = emu
+        .attach_device(0xf4..=0xf7, Box::new(Timer::default()))
+        .unwrap();
+    emu.bus_mut().write(0xf4, TIMER_ENABLE | TIMER_IRQ_ENABLE);
+    let until = RunUntil {
+        detect_halt: true,
+        max_instructions: Some(100),
+        ..RunUntil::default()
+    };
+    assert_eq!(emu.run_until(&until), StopReason::StepLimit);
+
+    emu.bus_mut().detach(timer).unwrap();
+    emu.reset();
+    assert_eq!(emu.run_until(&until), StopReason::SelfBranch(0x27));
+}
+
+#[test]
+fn loop_without_state_changes_is_detected() {
+    // $20: LDA #$01
+    // $22: TSTA
+    // $23: BNE $22
+    let mut emu = load_program(0x20, &[0xf0, 0x01, 0x09, 0x25, 0xfd]);
+    let until = RunUntil {
+        detect_halt: true,
+        max_instructions: Some(100),
+        ..RunUntil::default()
+    };
+
+    assert_eq!(emu.run_until(&until), StopReason::IdleLoop(0x22));
+}
+
+#[test]
+fn run_until_subroutine_returns() {
+    // $20: LDSP #$e0
+    // $22: JSR $40
+    // $40: INCA
+    // $41: INCA
+    // $42: RTS
+    let mut program = vec![0; 0x23];
+    program[..4].copy_from_slice(&[0x92, 0xe0, 0x34, 0x40]);
+    program[0x20..].copy_from_slice(&[0x07, 0x07, 0x43]);
+    let mut emu = load_program(0x20, &program);
+    emu.step().unwrap();
+    emu.step().unwrap();
+
+    let until = RunUntil {
+        subroutine_return: true,
+        ..RunUntil::default()
+    };
+    assert_eq!(emu.run_until(&until), StopReason::Completed);
+    assert_eq!(emu.reg_pc().get(), 0x24);
+    assert_eq!(emu.reg_a().get(), 2);
+
+    // The call has returned, so there is no subroutine left to wait for
+    assert_eq!(emu.run_until(&until), StopReason::NotInSubroutine);
+    assert_eq!(emu.reg_pc().get(), 0x24);
+}
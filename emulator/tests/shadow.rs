@@ -0,0 +1,88 @@
+use emulator::{Emulator, access::AccessKind, shadow::UninitializedRead, snapshot::Snapshot};
+
+// $20: LDA $50
+// $22: STA $51
+// $24: LDA $51
+// $26: (not in the image)
+fn program() -> Emulator {
+    let mut mem = [0; 256];
+    let mut initialized = [false; 256];
+    mem[0x20..0x26].copy_from_slice(&[0xf1, 0x50, 0xe1, 0x51, 0xf1, 0x51]);
+    initialized[0x20..0x26].fill(true);
+    mem[0xff] = 0x20;
+    initialized[0xff] = true;
+
+    let mut emu = Emulator::default();
+    emu.load_image(&mem, &initialized);
+    emu.reset();
+    emu
+}
+
+#[test]
+fn reads_before_any_write_are_reported() {
+    let mut emu = program();
+    for _ in 0..3 {
+        emu.step().unwrap();
+    }
+
+    assert_eq!(
+        emu.uninitialized_reads(),
+        [UninitializedRead {
+            pc: 0x20,
+            address: 0x50,
+            kind: AccessKind::Read,
+        }]
+    );
+    assert!(emu.is_initialized(0x51));
+    assert_eq!(
+        emu.get_debug_logs().back().unwrap(),
+        "Reading uninitialized 50 in instruction at 20"
+    );
+}
+
+#[test]
+fn running_off_the_end_of_the_program_is_reported() {
+    let mut emu = program();
+    for _ in 0..4 {
+        emu.step().unwrap();
+    }
+
+    assert_eq!(
+        emu.uninitialized_reads().last().unwrap().kind,
+        AccessKind::Fetch
+    );
+    assert_eq!(emu.uninitialized_reads().last().unwrap().pc, 0x26);
+
+    emu.reset();
+    assert!(emu.uninitialized_reads().is_empty());
+    assert!(!emu.is_initialized(0x51));
+}
+
+#[test]
+fn restoring_a_snapshot_keeps_the_initialized_mask() {
+    let mut emu = program();
+    emu.step().unwrap();
+    emu.step().unwrap();
+    let bytes = emu.snapshot().to_bytes();
+
+    let mut restored = Emulator::default();
+    restored.restore(&Snapshot::from_bytes(&bytes).unwrap());
+    assert!(restored.is_initialized(0x20));
+    assert!(restored.is_initialized(0x51));
+    assert!(!restored.is_initialized(0x50));
+
+    restored.step().unwrap();
+    assert!(restored.uninitialized_reads().is_empty());
+    restored.step().unwrap();
+    assert_eq!(
+        restored.uninitialized_reads(),
+        [UninitializedRead {
+            pc: 0x26,
+            address: 0x26,
+            kind: AccessKind::Fetch,
+        }]
+    );
+
+    restored.reset();
+    assert!(!restored.is_initialized(0x51));
+}
@@ -0,0 +1,78 @@
+mod common;
+use common::load_program;
+
+use emulator::snapshot::{Snapshot, SnapshotError};
+
+// $20: LDA #$05
+// $22: STA $40
+// $24: INCA
+// $25: BRA $22
+const PROGRAM: &[u8] = &[0xf0, 0x05, 0xe1, 0x40, 0x07, 0x21, 0xfb];
+
+#[test]
+fn restore_resumes_exactly_where_the_snapshot_was_taken() {
+    let mut emu = load_program(0x20, PROGRAM);
+    for _ in 0..5 {
+        emu.step().unwrap();
+    }
+    let bytes = emu.snapshot().to_bytes();
+    for _ in 0..7 {
+        emu.step().unwrap();
+    }
+
+    let mut restored = load_program(0x00, &[]);
+    restored.restore(&Snapshot::from_bytes(&bytes).unwrap());
+    assert_eq!(restored.reg_a().get(), 0x06);
+    assert_eq!(restored.memory_at(0x40), 0x06);
+    for _ in 0..7 {
+        restored.step().unwrap();
+    }
+    assert!(restored.snapshot() == emu.snapshot());
+
+    restored.reset();
+    assert_eq!(restored.memory_at(0x40), 0x00);
+    assert_eq!(restored.reg_pc().get(), 0x20);
+}
+
+#[test]
+fn rejects_foreign_and_future_files() {
+    let emu = load_program(0x20, PROGRAM);
+    let mut bytes = emu.snapshot().to_bytes();
+
+    assert!(matches!(
+        Snapshot::from_bytes(b"S1130000"),
+        Err(SnapshotError::NotASnapshot)
+    ));
+    assert!(matches!(
+        Snapshot::from_bytes(&bytes[..100]),
+        Err(SnapshotError::Truncated { .. })
+    ));
+    bytes[6] = 99;
+    assert!(matches!(
+        Snapshot::from_bytes(&bytes),
+        Err(SnapshotError::UnsupportedVersion(99))
+    ));
+}
+
+#[test]
+fn rejects_trailing_bytes_and_mid_instruction_states() {
+    let emu = load_program(0x20, PROGRAM);
+    let mut bytes = emu.snapshot().to_bytes();
+
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert!(matches!(
+        Snapshot::from_bytes(&longer),
+        Err(SnapshotError::TrailingBytes {
+            expected: 630,
+            found: 631
+        })
+    ));
+
+    // Q-state 2 is Execute, which a snapshot never records
+    bytes[17] = 2;
+    assert!(matches!(
+        Snapshot::from_bytes(&bytes),
+        Err(SnapshotError::InvalidQState(2))
+    ));
+}
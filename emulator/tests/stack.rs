@@ -0,0 +1,94 @@
+mod common;
+use common::load_program;
+
+use emulator::outcome::{ExecutionError, StackFault};
+
+#[test]
+fn pushing_below_the_region_overflows() {
+    // $20: LDSP #$e0
+    // $22: PSHA
+    // $23: PSHA
+    // $24: PSHA
+    // $25: NOP
+    let mut emu = load_program(0x20, &[0x92, 0xe0, 0x10, 0x10, 0x10, 0x00]);
+    emu.set_stack_region(0xde..=0xdf);
+
+    for _ in 0..3 {
+        emu.step().unwrap();
+    }
+    assert_eq!(
+        emu.step(),
+        Err(ExecutionError::Stack {
+            pc: 0x24,
+            sp: 0xdd,
+            fault: StackFault::Overflow,
+        })
+    );
+    assert_eq!(emu.stack_high_water_mark(), Some(0xde));
+    assert_eq!(emu.max_stack_depth(), Some(2));
+
+    // The fault does not halt the CPU
+    emu.step().unwrap();
+    assert_eq!(emu.reg_pc().get(), 0x26);
+}
+
+#[test]
+fn overflowing_still_counts_the_next_breakpoint() {
+    // $20: LDSP #$e0
+    // $22: PSHA
+    // $23: PSHA
+    // $24: NOP
+    let mut emu = load_program(0x20, &[0x92, 0xe0, 0x10, 0x10, 0x00]);
+    emu.set_stack_region(0xdf..=0xdf);
+    emu.breakpoints_mut().add(0x24);
+
+    emu.step().unwrap();
+    emu.step().unwrap();
+    assert!(matches!(
+        emu.step(),
+        Err(ExecutionError::Stack {
+            pc: 0x23,
+            fault: StackFault::Overflow,
+            ..
+        })
+    ));
+    assert_eq!(emu.reg_pc().get(), 0x24);
+    assert_eq!(emu.breakpoints().get(0x24).unwrap().hit_count(), 1);
+}
+
+#[test]
+fn pulling_from_an_empty_stack_underflows() {
+    // $20: LDSP #$e0
+    // $22: PULA
+    let mut emu = load_program(0x20, &[0x92, 0xe0, 0x14]);
+    emu.set_stack_region(0xc0..=0xdf);
+
+    emu.step().unwrap();
+    assert_eq!(emu.max_stack_depth(), Some(0));
+    assert_eq!(
+        emu.step(),
+        Err(ExecutionError::Stack {
+            pc: 0x22,
+            sp: 0xe1,
+            fault: StackFault::Underflow,
+        })
+    );
+}
+
+#[test]
+fn moving_sp_out_of_the_region_faults() {
+    // $20: LDSP #$e0
+    // $22: LDSP #$40
+    let mut emu = load_program(0x20, &[0x92, 0xe0, 0x92, 0x40]);
+    emu.set_stack_region(0xc0..=0xdf);
+
+    emu.step().unwrap();
+    assert_eq!(
+        emu.step(),
+        Err(ExecutionError::Stack {
+            pc: 0x22,
+            sp: 0x40,
+            fault: StackFault::OutOfRegion,
+        })
+    );
+}
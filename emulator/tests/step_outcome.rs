@@ -0,0 +1,45 @@
+mod common;
+use common::load_program;
+
+use emulator::{
+    breakpoint::StopReason,
+    outcome::{ExecutionError, StepOutcome},
+};
+
+#[test]
+fn invalid_opcode_faults_instead_of_panicking() {
+    // $20: INCA
+    // $21: (invalid)
+    let mut emu = load_program(0x20, &[0x07, 0x03]);
+
+    assert_eq!(emu.step(), Ok(StepOutcome::Executed));
+    let fault = ExecutionError::InvalidOpcode {
+        pc: 0x21,
+        opcode: 0x03,
+    };
+    assert_eq!(emu.step(), Err(fault));
+    assert_eq!(emu.reg_pc().get(), 0x21);
+    assert_eq!(emu.reg_a().get(), 1);
+
+    let halted = ExecutionError::Halted {
+        pc: 0x21,
+        opcode: 0x03,
+    };
+    assert_eq!(emu.step(), Err(halted));
+    assert_eq!(emu.run_until_break(10), StopReason::Fault(halted));
+
+    emu.reset();
+    assert_eq!(emu.fault(), None);
+    assert_eq!(emu.step(), Ok(StepOutcome::Executed));
+}
+
+#[test]
+fn step_reports_arriving_at_a_breakpoint() {
+    // $20: NOP
+    // $21: NOP
+    let mut emu = load_program(0x20, &[0x00, 0x00]);
+    emu.breakpoints_mut().add(0x21);
+
+    assert_eq!(emu.step(), Ok(StepOutcome::BreakpointHit(0x21)));
+    assert_eq!(emu.step(), Ok(StepOutcome::Executed));
+}
@@ -0,0 +1,85 @@
+mod common;
+use common::load_program;
+
+use emulator::{
+    bus::Device,
+    devices::{TIMER_ENABLE, TIMER_EXPIRED, TIMER_IRQ_ENABLE, TIMER_ONE_SHOT, Timer},
+    interrupt::IRQ_VECTOR,
+};
+
+#[test]
+fn periodic_timer_interrupts_at_a_fixed_rate() {
+    // $20: LDSP #$e0
+    // $22: LDA #$64
+    // $24: STA $f1
+    // $26: LDA #$03
+    // $28: STA $f0
+    // $2a: BRA $2a
+    // $40: INC $50
+    // $42: LDA #$80
+    // $44: STA $f2
+    // $46: RTI
+    let mut program = vec![0; 0x27];
+    program[..0x0c].copy_from_slice(&[
+        0x92, 0xe0, 0xf0, 0x64, 0xe1, 0xf1, 0xf0, 0x03, 0xe1, 0xf0, 0x21, 0xfe,
+    ]);
+    program[0x20..].copy_from_slice(&[0x37, 0x50, 0xf0, 0x80, 0xe1, 0xf2, 0x44]);
+    let mut emu = load_program(0x20, &program);
+    emu.write_memory(IRQ_VECTOR, 0x40);
+    let timer = emu
+        .attach_device(0xf0..=0xf3, Box::new(Timer::default()))
+        .unwrap();
+
+    emu.run_instructions(4).unwrap();
+    let start = emu.clk_count();
+    emu.step().unwrap();
+    assert_eq!(
+        emu.bus().device_as::<Timer>(timer).unwrap().period_cycles(),
+        100
+    );
+
+    emu.run_cycles(1000).unwrap();
+    let timer = emu.bus().device_as::<Timer>(timer).unwrap();
+    let expirations = timer.expirations();
+    assert_eq!(expirations, ((emu.clk_count() - start) / 100) as u64);
+    let handled = emu.memory_at(0x50_u8) as u64;
+    assert!(
+        handled == expirations || (handled + 1 == expirations && timer.is_expired()),
+        "{} interrupts handled for {} expirations",
+        handled,
+        expirations
+    );
+}
+
+#[test]
+fn one_shot_timer_stops_after_expiring() {
+    // $20: NOP
+    // $21: BRA $20
+    let mut emu = load_program(0x20, &[0x00, 0x21, 0xfd]);
+    let id = emu
+        .attach_device(0xf0..=0xf3, Box::new(Timer::default()))
+        .unwrap();
+    emu.bus_mut().write(0xf1, 2);
+    emu.bus_mut()
+        .write(0xf0, TIMER_ENABLE | TIMER_ONE_SHOT | 0x10);
+    let timer = emu.bus().device_as::<Timer>(id).unwrap();
+    assert_eq!(timer.period_cycles(), 16);
+    assert!(timer.is_running());
+
+    emu.run_cycles(100).unwrap();
+    let timer = emu.bus().device_as::<Timer>(id).unwrap();
+    assert!(!timer.is_running());
+    assert_eq!(timer.expirations(), 1);
+    assert!(!timer.irq(), "interrupts are not enabled");
+    assert!(!emu.irq_pending());
+    assert_eq!(emu.memory_at(0xf2_u8), TIMER_EXPIRED);
+
+    emu.bus_mut().write(0xf2, TIMER_EXPIRED);
+    assert_eq!(emu.memory_at(0xf2_u8), 0);
+
+    emu.bus_mut().write(0xf0, TIMER_ENABLE | TIMER_IRQ_ENABLE);
+    emu.run_cycles(2).unwrap();
+    let timer = emu.bus().device_as::<Timer>(id).unwrap();
+    assert!(timer.is_running(), "periodic once restarted");
+    assert!(emu.irq_pending());
+}
@@ -0,0 +1,63 @@
+mod common;
+use common::load_program;
+
+use emulator::trace::TraceRegisters;
+
+// $20: LDA #$05
+// $22: STA $40
+const STORE: &[u8] = &[0xf0, 0x05, 0xe1, 0x40];
+
+#[test]
+fn records_every_instruction_while_tracing() {
+    let mut emu = load_program(0x20, STORE);
+    emu.start_trace();
+    emu.step().unwrap();
+    emu.step().unwrap();
+
+    let trace = emu.stop_trace().unwrap();
+    assert!(!emu.is_tracing());
+    assert_eq!(trace.len(), 2);
+
+    let store = &trace.records()[1];
+    assert_eq!(store.pc, 0x22);
+    assert_eq!(store.opcode, Some(0xe1));
+    assert_eq!(store.mnemonic, "STA");
+    assert_eq!(store.before.a, 0x05);
+    assert_eq!(
+        store.after,
+        TraceRegisters {
+            pc: 0x24,
+            ..store.before
+        }
+    );
+    assert_eq!(store.writes, [(0x40, 0x05)]);
+    assert_eq!(store.cycle, trace.records()[0].cycle + 2);
+}
+
+#[test]
+fn exports_csv_and_json_lines() {
+    let mut emu = load_program(0x20, STORE);
+    emu.start_trace();
+    emu.step().unwrap();
+    emu.step().unwrap();
+    let trace = emu.trace().unwrap();
+
+    let mut csv = Vec::new();
+    trace.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("cycle,pc,opcode,mnemonic,"));
+    assert_eq!(
+        lines[2],
+        "2,22,e1,STA,05,00,00,00,00,05,00,00,00,24,00,40=05"
+    );
+
+    let mut jsonl = Vec::new();
+    trace.write_jsonl(&mut jsonl).unwrap();
+    let jsonl = String::from_utf8(jsonl).unwrap();
+    assert_eq!(
+        jsonl.lines().nth(1).unwrap(),
+        r#"{"cycle":2,"pc":34,"opcode":225,"mnemonic":"STA","before":{"a":5,"x":0,"y":0,"sp":0,"pc":34,"cc":0},"after":{"a":5,"x":0,"y":0,"sp":0,"pc":36,"cc":0},"writes":[{"address":64,"value":5}]}"#
+    );
+}
@@ -0,0 +1,92 @@
+mod common;
+use common::load_program;
+
+use emulator::{
+    access::AccessKind,
+    breakpoint::StopReason,
+    watchpoint::{WatchKind, WatchpointHit},
+};
+
+// $20: LDX #$30
+// $22: LDA #$aa
+// $24: STA ,X+
+// $25: BRA $24
+const RUNAWAY_STORE: &[u8] = &[0x90, 0x30, 0xf0, 0xaa, 0xe5, 0x21, 0xfd];
+
+#[test]
+fn records_fetches_reads_and_writes() {
+    // $20: LDA $40
+    // $22: STA $41
+    let mut emu = load_program(0x20, &[0xf1, 0x40, 0xe1, 0x41]);
+
+    emu.step().unwrap();
+    let kinds: Vec<_> = emu.last_accesses().iter().map(|a| a.kind).collect();
+    assert_eq!(
+        kinds,
+        [AccessKind::Fetch, AccessKind::Fetch, AccessKind::Read]
+    );
+    assert_eq!(emu.last_accesses()[2].address, 0x40);
+
+    emu.step().unwrap();
+    let write = emu.last_accesses()[2];
+    assert_eq!(write.kind, AccessKind::Write);
+    assert_eq!(write.address, 0x41);
+}
+
+#[test]
+fn write_watchpoint_catches_runaway_store_into_code() {
+    let mut emu = load_program(0x20, RUNAWAY_STORE);
+    let id = emu.watchpoints_mut().add(0x20..=0x26, WatchKind::Write);
+    emu.breakpoints_mut().add(0x50);
+
+    // X wraps from $30 up through $ff and into the program at $20
+    let StopReason::Watchpoint(WatchpointHit {
+        id: hit_id,
+        pc,
+        access,
+    }) = emu.run_until_break(10_000)
+    else {
+        panic!("expected a watchpoint stop");
+    };
+    assert_eq!(hit_id, id);
+    assert_eq!(pc, 0x24);
+    assert_eq!(access.address, 0x20);
+    assert_eq!(access.value, 0xaa);
+    assert_eq!(access.previous, 0x90);
+}
+
+#[test]
+fn change_watchpoint_ignores_writes_of_the_same_value() {
+    // $20: CLR $40
+    // $22: CLR $40
+    // $24: INC $40
+    let mut emu = load_program(0x20, &[0x35, 0x40, 0x35, 0x40, 0x37, 0x40]);
+    emu.watchpoints_mut().add_address(0x40, WatchKind::Change);
+
+    let StopReason::Watchpoint(hit) = emu.run_until_break(10) else {
+        panic!("expected a watchpoint stop");
+    };
+    assert_eq!(hit.pc, 0x24);
+    assert_eq!(hit.access.value, 1);
+}
+
+#[test]
+fn lda_indexed_by_y_loads_memory_not_the_address() {
+    // $20: LDY #$10
+    // $22: LDA $30,Y
+    // $24: LDA #$05
+    // $26: LDA A,Y
+    let mut emu = load_program(0x20, &[0x91, 0x10, 0xf9, 0x30, 0xf0, 0x05, 0xfa]);
+    emu.write_memory(0x40, 0x5a);
+    emu.write_memory(0x15, 0xa5);
+
+    emu.run_instructions(2).unwrap();
+    assert_eq!(emu.reg_a().get(), 0x5a);
+    assert_eq!(emu.last_accesses()[2].kind, AccessKind::Read);
+    assert_eq!(emu.last_accesses()[2].address, 0x40);
+
+    emu.run_instructions(2).unwrap();
+    assert_eq!(emu.reg_a().get(), 0xa5);
+    assert_eq!(emu.last_accesses()[1].kind, AccessKind::Read);
+    assert_eq!(emu.last_accesses()[1].address, 0x15);
+}
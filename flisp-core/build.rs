@@ -0,0 +1,103 @@
+//! Generates the opcode table in `src/opcode.rs` from `all.json` at the workspace root.
+
+use std::{collections::BTreeMap, env, fs, path::Path};
+
+use serde::Deserialize;
+
+/// One opcode of all.json. Unused opcodes have a null `clk` and `bytes` and an empty
+/// `name` and `mode`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    hex: String,
+    clk: Option<u8>,
+    name: String,
+    bytes: Option<u8>,
+    mode: String,
+}
+
+fn main() {
+    let json_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../all.json");
+    println!("cargo:rerun-if-changed={}", json_path.display());
+
+    let json = fs::read_to_string(&json_path).expect("failed to read all.json");
+    let entries: BTreeMap<String, Entry> =
+        serde_json::from_str(&json).unwrap_or_else(|e| panic!("invalid all.json: {}", e));
+    let mut table: Vec<Option<String>> = vec![None; 256];
+    for (key, entry) in entries {
+        let opcode = u8::from_str_radix(&entry.hex, 16)
+            .unwrap_or_else(|_| panic!("invalid opcode `{}` in all.json", entry.hex));
+        assert_eq!(
+            key, entry.hex,
+            "all.json entry {} has hex {}",
+            key, entry.hex
+        );
+        let (bytes, clocks) = match (entry.bytes, entry.clk) {
+            (Some(bytes), Some(clocks)) if !entry.name.is_empty() && !entry.mode.is_empty() => {
+                (bytes, clocks)
+            }
+            (None, None) if entry.name.is_empty() && entry.mode.is_empty() => continue,
+            _ => panic!("all.json entry {} is only partly defined", key),
+        };
+        let (mnemonic, mode) = mnemonic_and_mode(&entry);
+        table[opcode as usize] = Some(format!(
+            "OpcodeInfo {{ opcode: 0x{:02x}, mnemonic: {:?}, mode: {}, bytes: {}, clocks: {} }}",
+            opcode, mnemonic, mode, bytes, clocks
+        ));
+    }
+
+    let mut out = String::from("[\n");
+    for entry in table {
+        match entry {
+            Some(info) => out.push_str(&format!("    Some({}),\n", info)),
+            None => out.push_str("    None,\n"),
+        }
+    }
+    out.push(']');
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("opcodes.rs");
+    fs::write(out_path, out).expect("failed to write opcode table");
+}
+
+fn mnemonic_and_mode(entry: &Entry) -> (String, String) {
+    let (mnemonic, registers) = match entry.name.split_once(' ') {
+        Some((mnemonic, registers)) => (mnemonic, Some(registers)),
+        None => (entry.name.as_str(), None),
+    };
+
+    let mode = match (entry.mode.as_str(), registers) {
+        ("ih", Some(registers)) => {
+            let (from, to) = registers.split_once(',').expect("register pair");
+            format!("RegisterPair({}, {})", register(from), register(to))
+        }
+        ("ih", None) => "Inherent".to_string(),
+        ("im", _) => "Immediate".to_string(),
+        ("ab", _) => "Absolute".to_string(),
+        ("pc", _) => "Relative".to_string(),
+        ("ns", _) => "Indexed(SP)".to_string(),
+        ("nx", _) => "Indexed(X)".to_string(),
+        ("ny", _) => "Indexed(Y)".to_string(),
+        ("ax", _) => "AccumulatorIndexed(X)".to_string(),
+        ("ay", _) => "AccumulatorIndexed(Y)".to_string(),
+        (mode, _) => {
+            let (kind, reg) = match mode.as_bytes() {
+                [b'+', r] => ("PreIncrement", r),
+                [b'-', r] => ("PreDecrement", r),
+                [r, b'+'] => ("PostIncrement", r),
+                [r, b'-'] => ("PostDecrement", r),
+                _ => panic!("unknown addressing mode `{}` in all.json", mode),
+            };
+            format!("{}({})", kind, (*reg as char).to_ascii_uppercase())
+        }
+    };
+    (mnemonic.to_string(), mode)
+}
+
+/// all.json abbreviates CC and SP in register pairs
+fn register(name: &str) -> &str {
+    match name {
+        "C" => "CC",
+        "S" => "SP",
+        other => other,
+    }
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+
+use crate::opcode;
+pub use crate::opcode::{AddressingMode, RegisterName};
+
+/// One decoded instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u8,
+    pub opcode: u8,
+    pub mnemonic: &'static str,
+    pub mode: AddressingMode,
+    /// The operand byte, for modes that have one
+    pub operand: Option<u8>,
+}
+
+impl Instruction {
+    /// Number of bytes the instruction occupies
+    pub fn size(&self) -> u8 {
+        opcode::info(self.opcode).map_or(1, |info| info.bytes)
+    }
+
+    /// Address a relative branch jumps to when taken
+    pub fn branch_target(&self) -> Option<u8> {
+        match (self.mode, self.operand) {
+            (AddressingMode::Relative, Some(offset)) => {
+                Some(self.address.wrapping_add(2).wrapping_add(offset))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Formats the instruction in assembler syntax, e.g. `LDA $05,X` or `BNE $2C`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use AddressingMode as M;
+
+        write!(f, "{}", self.mnemonic)?;
+        let operand = self.operand.unwrap_or_default();
+        match self.mode {
+            M::Inherent => Ok(()),
+            M::Immediate => write!(f, " #${:02X}", operand),
+            M::Absolute => write!(f, " ${:02X}", operand),
+            M::Relative => write!(f, " ${:02X}", self.branch_target().unwrap_or_default()),
+            M::Indexed(reg) => write!(f, " ${:02X},{}", operand, reg),
+            M::AccumulatorIndexed(reg) => write!(f, " A,{}", reg),
+            M::PostIncrement(reg) => write!(f, " ,{}+", reg),
+            M::PostDecrement(reg) => write!(f, " ,{}-", reg),
+            M::PreIncrement(reg) => write!(f, " ,+{}", reg),
+            M::PreDecrement(reg) => write!(f, " ,-{}", reg),
+            M::RegisterPair(from, to) => write!(f, " {},{}", from, to),
+        }
+    }
+}
+
+/// Decodes the instruction at the start of `bytes`, which was read from `address`.
+///
+/// Returns: `None` if the opcode is invalid or its operand byte is missing
+pub fn decode(address: u8, bytes: &[u8]) -> Option<Instruction> {
+    let opcode = *bytes.first()?;
+    let info = opcode::info(opcode)?;
+    let operand = if info.bytes > 1 {
+        Some(*bytes.get(1)?)
+    } else {
+        None
+    };
+    Some(Instruction {
+        address,
+        opcode,
+        mnemonic: info.mnemonic,
+        mode: info.mode,
+        operand,
+    })
+}
+
+/// Decodes the instruction at `address` in a full memory image. Operands wrap around
+/// from $FF to $00.
+pub fn decode_at(memory: &[u8; 256], address: u8) -> Option<Instruction> {
+    let bytes = [
+        memory[address as usize],
+        memory[address.wrapping_add(1) as usize],
+    ];
+    decode(address, &bytes)
+}
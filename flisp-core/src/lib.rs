@@ -1,2 +1,4 @@
+pub mod disasm;
 pub mod fmem;
+pub mod opcode;
 pub mod s19;
@@ -0,0 +1,82 @@
+use std::fmt;
+
+/// Register named in an operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterName {
+    A,
+    CC,
+    X,
+    Y,
+    SP,
+}
+
+impl fmt::Display for RegisterName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::A => "A",
+            Self::CC => "CC",
+            Self::X => "X",
+            Self::Y => "Y",
+            Self::SP => "SP",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressingMode {
+    /// No operand, e.g. `NOP`
+    Inherent,
+    /// `#Data`
+    Immediate,
+    /// `Adr`
+    Absolute,
+    /// `Adr`, encoded as an offset from the next instruction
+    Relative,
+    /// `n,X`, `n,Y` or `n,SP`
+    Indexed(RegisterName),
+    /// `A,X` or `A,Y`
+    AccumulatorIndexed(RegisterName),
+    /// `,X+`
+    PostIncrement(RegisterName),
+    /// `,X-`
+    PostDecrement(RegisterName),
+    /// `,+X`
+    PreIncrement(RegisterName),
+    /// `,-X`
+    PreDecrement(RegisterName),
+    /// `TFR` and `EXG` operands, e.g. `A,CC`
+    RegisterPair(RegisterName, RegisterName),
+}
+
+/// Encoding and timing of one opcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpcodeInfo {
+    pub opcode: u8,
+    pub mnemonic: &'static str,
+    pub mode: AddressingMode,
+    /// Instruction length including the opcode
+    pub bytes: u8,
+    /// Clock cycles to fetch and execute the instruction
+    pub clocks: u8,
+}
+
+/// Every FLISP opcode, indexed by opcode. Generated from `all.json` at the workspace root.
+pub static OPCODES: [Option<OpcodeInfo>; 256] = {
+    use AddressingMode::*;
+    use RegisterName::*;
+    include!(concat!(env!("OUT_DIR"), "/opcodes.rs"))
+};
+
+/// Metadata for `opcode`, or `None` if it is not a FLISP instruction
+pub fn info(opcode: u8) -> Option<&'static OpcodeInfo> {
+    OPCODES[opcode as usize].as_ref()
+}
+
+/// The opcode encoding `mnemonic` with operands in `mode`
+pub fn find(mnemonic: &str, mode: AddressingMode) -> Option<&'static OpcodeInfo> {
+    OPCODES
+        .iter()
+        .flatten()
+        .find(|info| info.mnemonic == mnemonic && info.mode == mode)
+}
@@ -0,0 +1,32 @@
+use flisp_core::opcode::{self, AddressingMode, OPCODES, RegisterName};
+
+#[test]
+fn table_covers_every_valid_opcode() {
+    let invalid: Vec<_> = (0..=0xff_u8)
+        .filter(|&op| opcode::info(op).is_none())
+        .collect();
+    assert_eq!(invalid, [0x03, 0x04, 0xdf, 0xe0, 0xef, 0xff]);
+
+    for (index, info) in OPCODES.iter().enumerate() {
+        if let Some(info) = info {
+            assert_eq!(info.opcode as usize, index);
+        }
+    }
+}
+
+#[test]
+fn register_pairs_use_assembler_register_names() {
+    let tfr = opcode::info(0x18).unwrap();
+    assert_eq!(tfr.mnemonic, "TFR");
+    assert_eq!(
+        tfr.mode,
+        AddressingMode::RegisterPair(RegisterName::A, RegisterName::CC)
+    );
+    assert_eq!((tfr.bytes, tfr.clocks), (1, 2));
+
+    let exg = opcode::find(
+        "EXG",
+        AddressingMode::RegisterPair(RegisterName::X, RegisterName::SP),
+    );
+    assert_eq!(exg.map(|info| info.opcode), Some(0xbf));
+}
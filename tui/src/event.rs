@@ -1,6 +1,16 @@
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
 
-use crate::ui::EmulatorVisualizer;
+use emulator::{
+    breakpoint::StopReason,
+    devices::{Console, Keypad, Switches},
+    snapshot::Snapshot,
+};
+
+use crate::ui::{EmulatorVisualizer, find_device};
+
+/// Upper bound on instructions executed by a single continue, so a program without
+/// breakpoints cannot hang the UI
+const CONTINUE_STEP_LIMIT: usize = 100_000;
 
 pub fn handle_event(ui: &mut EmulatorVisualizer, event: Event) {
     match event {
@@ -17,10 +27,165 @@ fn handle_key_event(ui: &mut EmulatorVisualizer, key_event: KeyEvent) {
 }
 
 fn handle_key_press(ui: &mut EmulatorVisualizer, key_code: KeyCode) {
+    if ui.console_input {
+        type_into_console(ui, key_code);
+        return;
+    }
+    if ui.keypad_entry {
+        ui.keypad_entry = false;
+        if let KeyCode::Char(c) = key_code
+            && let Some(key) = c.to_digit(16)
+        {
+            press_key(ui, key as u8);
+        }
+        return;
+    }
+
     match key_code {
         KeyCode::Char('q') => ui.exit(),
-        KeyCode::Char('s') => ui.program.step(),
+        KeyCode::Char('s') => {
+            if let Err(error) = ui.program.step() {
+                ui.program.debug_log(error.to_string());
+            }
+        }
+        KeyCode::Char('S') => {
+            if let Err(error) = ui.program.micro_step() {
+                ui.program.debug_log(error.to_string());
+            }
+        }
+        KeyCode::Char('p') => {
+            if !ui.program.step_back() {
+                ui.program.debug_log("No earlier step recorded".to_string());
+            }
+        }
+        KeyCode::Char('C') => {
+            if let Some(address) = ui.program.reverse_continue() {
+                ui.program
+                    .debug_log(format!("Breakpoint at {:02x}", address));
+            }
+        }
         KeyCode::Char('r') => ui.program.reset(),
+        KeyCode::Char('w') => {
+            let msg = match ui.program.snapshot().save(&ui.state_path) {
+                Ok(()) => format!("Saved state to {}", ui.state_path.display()),
+                Err(e) => format!("Failed to save state: {}", e),
+            };
+            ui.program.debug_log(msg);
+        }
+        KeyCode::Char('l') => {
+            let msg = match Snapshot::load(&ui.state_path) {
+                Ok(snapshot) => {
+                    ui.program.restore(&snapshot);
+                    format!("Loaded state from {}", ui.state_path.display())
+                }
+                Err(e) => format!("Failed to load state: {}", e),
+            };
+            ui.program.debug_log(msg);
+        }
+        KeyCode::Char('c') => {
+            let reason = ui.program.run_until_break(CONTINUE_STEP_LIMIT);
+            report_stop(ui, reason);
+        }
+        KeyCode::Char('o') => {
+            let reason = ui.program.step_over(CONTINUE_STEP_LIMIT);
+            report_stop(ui, reason);
+        }
+        KeyCode::Char('O') => {
+            if ui.program.call_stack().is_empty() {
+                ui.program.debug_log("Not inside a subroutine".to_string());
+            }
+            let reason = ui.program.step_out(CONTINUE_STEP_LIMIT);
+            report_stop(ui, reason);
+        }
+        KeyCode::Char('i') => {
+            let asserted = !ui.program.irq_line();
+            ui.program.set_irq_line(asserted);
+            let state = if asserted { "asserted" } else { "released" };
+            ui.program.debug_log(format!("IRQ line {}", state));
+        }
+        KeyCode::Char(c @ '0'..='7') => {
+            let bit = c as u8 - b'0';
+            match find_device::<Switches>(ui.program) {
+                Some(id) => {
+                    let switches = ui.program.bus_mut().device_as_mut::<Switches>(id).unwrap();
+                    switches.toggle(bit);
+                }
+                None => ui.program.debug_log("No switches attached".to_string()),
+            }
+        }
+        KeyCode::Char('k') => {
+            if find_device::<Keypad>(ui.program).is_some() {
+                ui.keypad_entry = true;
+            } else {
+                ui.program.debug_log("No keypad attached".to_string());
+            }
+        }
+        KeyCode::Char('t') => {
+            if find_device::<Console>(ui.program).is_some() {
+                ui.console_input = true;
+            } else {
+                ui.program.debug_log("No console attached".to_string());
+            }
+        }
+        KeyCode::Char('B') => {
+            let pc = ui.program.reg_pc().get();
+            ui.program.breakpoints_mut().toggle(pc);
+        }
         _ => {}
     }
 }
+
+fn type_into_console(ui: &mut EmulatorVisualizer, key_code: KeyCode) {
+    let byte = match key_code {
+        KeyCode::Esc => {
+            ui.console_input = false;
+            return;
+        }
+        KeyCode::Enter => b'\n',
+        KeyCode::Backspace => 0x08,
+        KeyCode::Tab => b'\t',
+        KeyCode::Char(c) if c.is_ascii() => c as u8,
+        _ => return,
+    };
+    if let Some(id) = find_device::<Console>(ui.program) {
+        let console = ui.program.bus_mut().device_as_mut::<Console>(id).unwrap();
+        console.send_input(&[byte]);
+    }
+}
+
+fn press_key(ui: &mut EmulatorVisualizer, key: u8) {
+    let Some(id) = find_device::<Keypad>(ui.program) else {
+        return;
+    };
+    let keypad = ui.program.bus_mut().device_as_mut::<Keypad>(id).unwrap();
+    if !keypad.press(key) {
+        ui.program
+            .debug_log(format!("Key {:x} lost, previous key not read yet", key));
+    }
+}
+
+fn report_stop(ui: &mut EmulatorVisualizer, reason: StopReason) {
+    match reason {
+        StopReason::Breakpoint(address) => {
+            ui.program
+                .debug_log(format!("Breakpoint at {:02x}", address));
+        }
+        StopReason::Watchpoint(hit) => ui.program.debug_log(format!(
+            "Watchpoint: {:02x} accessed at {:02x}",
+            hit.access.address, hit.pc
+        )),
+        StopReason::Fault(error) => ui.program.debug_log(error.to_string()),
+        StopReason::SelfBranch(pc) => ui.program.debug_log(format!(
+            "Program halted in a branch to itself at {:02x}",
+            pc
+        )),
+        StopReason::IdleLoop(pc) => ui
+            .program
+            .debug_log(format!("Program stuck in a loop at {:02x}", pc)),
+        StopReason::StepLimit
+        | StopReason::CycleLimit
+        | StopReason::Completed
+        | StopReason::NotInSubroutine
+        | StopReason::ReachedPc(_) => {}
+    }
+}
@@ -45,10 +45,21 @@ impl<'a> UiState<'a> {
             InputMode::Normal => Paragraph::new(vec![
                 line("<Space>", "Start/Pause execution"),
                 line("<s>", "Step one instruction"),
+                line("<S>", "Step one clock cycle"),
+                line("<o>", "Step over subroutine call"),
+                line("<O>", "Step out of subroutine"),
+                line("<p>", "Step back one instruction"),
+                line("<c>", "Continue until breakpoint"),
+                line("<C>", "Reverse continue to previous breakpoint"),
                 // line("<r>", "Open register editor"),
                 line("<m>", "Open memory editor"),
-                // line("<b>", "Open breakpoint manager"),
-                // line("<B>", "Quick toggle breakpoint at current PC"),
+                line("<w>", "Save machine state"),
+                line("<l>", "Load machine state"),
+                line("<B>", "Quick toggle breakpoint at current PC"),
+                line("<i>", "Toggle IRQ line"),
+                line("<0-7>", "Toggle switch"),
+                line("<k><0-f>", "Press keypad key"),
+                line("<t>", "Type into console, <Esc> to stop"),
                 line("<q>", "Quit program"),
             ]),
             InputMode::MemoryEditor => todo!(),
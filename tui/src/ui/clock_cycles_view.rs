@@ -1,14 +1,47 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    text::Line,
     widgets::{Paragraph, Widget},
 };
 
-use emulator::Emulator;
+use emulator::{Emulator, access::AccessKind, micro::ControlState};
 
 pub fn clock_cycles_view(program: &Emulator, area: Rect, buf: &mut Buffer) {
     let clk_cycles = program.clk_count();
     let clk_cycles_str = format!(" CLK Cycles: {}", clk_cycles);
 
-    Paragraph::new(clk_cycles_str).render(area, buf);
+    let q_state = match program.instruction_clock() {
+        Some((clock, clocks)) => {
+            let action = match program.control_state() {
+                Some(ControlState::Fetch) => "fetch",
+                Some(ControlState::Access(AccessKind::Fetch)) => "operand",
+                Some(ControlState::Access(AccessKind::Read)) => "read",
+                Some(ControlState::Access(AccessKind::Write)) => "write",
+                Some(ControlState::Internal) | None => "internal",
+            };
+            format!(
+                " Q: {:?} {}/{} {}",
+                program.q_state(),
+                clock,
+                clocks,
+                action
+            )
+        }
+        None => format!(" Q: {:?}", program.q_state()),
+    };
+    let internals = format!(
+        " I:{:02x} R:{:02x} TA:{:02x} LD:{:02x}",
+        program.reg_i().get(),
+        program.reg_r().get(),
+        program.reg_ta().get(),
+        program.reg_ld().get(),
+    );
+
+    Paragraph::new(vec![
+        Line::from(clk_cycles_str),
+        Line::from(q_state),
+        Line::from(internals),
+    ])
+    .render(area, buf);
 }
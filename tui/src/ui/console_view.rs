@@ -0,0 +1,41 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use emulator::{Emulator, bus::DeviceId, devices::Console};
+
+/// Shows the most recent lines written to the console
+pub fn console_view(
+    program: &Emulator,
+    console: DeviceId,
+    typing: bool,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let Some(console) = program.bus().device_as::<Console>(console) else {
+        return;
+    };
+    let text = String::from_utf8_lossy(console.output()).replace('\r', "");
+    let rows = area.height.saturating_sub(2) as usize;
+    let all: Vec<&str> = text.split('\n').collect();
+    let lines: Vec<Line> = all[all.len().saturating_sub(rows)..]
+        .iter()
+        .map(|line| Line::raw(line.to_string()))
+        .collect();
+
+    let title = if typing {
+        Line::from("Console (typing, <Esc> to stop)").fg(Color::Green)
+    } else {
+        Line::from("Console")
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .title(title);
+    Paragraph::new(lines).block(block).render(area, buf);
+}
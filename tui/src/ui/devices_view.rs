@@ -0,0 +1,109 @@
+use std::ops::RangeInclusive;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Stylize},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use emulator::{
+    Emulator,
+    bus::{Device, DeviceId},
+    devices::{Console, Keypad, Leds, SevenSegment, Switches, Timer},
+};
+
+/// Shows the state of the lab board devices attached to the bus
+pub fn devices_view(program: &Emulator, area: Rect, buf: &mut Buffer) {
+    let bus = program.bus();
+    let lines: Vec<Line> = bus
+        .devices()
+        .map(|(id, range, device)| {
+            let mut line = vec![
+                Span::default()
+                    .content(format!("{:<6}", address_range(&range)))
+                    .fg(Color::DarkGray),
+                Span::raw(format!("{:<10}", device.name())),
+            ];
+            line.extend(device_state(program, id));
+            Line::from(line)
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .title(Line::from("Devices"));
+    Paragraph::new(lines).block(block).render(area, buf);
+}
+
+fn device_state(program: &Emulator, id: DeviceId) -> Vec<Span<'static>> {
+    let bus = program.bus();
+    if let Some(switches) = bus.device_as::<Switches>(id) {
+        return bits(switches.value(), Color::Yellow);
+    }
+    if let Some(leds) = bus.device_as::<Leds>(id) {
+        return bits(leds.value(), Color::Red);
+    }
+    if let Some(display) = bus.device_as::<SevenSegment>(id) {
+        return vec![
+            Span::default()
+                .content(display.text())
+                .bg(Color::Black)
+                .fg(Color::LightRed),
+        ];
+    }
+    if let Some(keypad) = bus.device_as::<Keypad>(id) {
+        let text = match keypad.pending() {
+            Some(key) => format!("key {:x}", key),
+            None => "-".to_string(),
+        };
+        return vec![Span::raw(text)];
+    }
+    if let Some(console) = bus.device_as::<Console>(id) {
+        let text = if console.has_input() { "input" } else { "" };
+        return vec![Span::raw(text)];
+    }
+    if let Some(timer) = bus.device_as::<Timer>(id) {
+        let mut state = vec![Span::raw(format!("{:>3}", timer.counter()))];
+        if timer.is_expired() {
+            state.push(Span::default().content(" exp").fg(Color::Yellow));
+        } else if !timer.is_running() {
+            state.push(Span::default().content(" off").fg(Color::DarkGray));
+        }
+        return state;
+    }
+    Vec::new()
+}
+
+/// The first attached device of type `T`
+pub(crate) fn find_device<T: Device>(program: &Emulator) -> Option<DeviceId> {
+    let bus = program.bus();
+    bus.devices()
+        .map(|(id, _, _)| id)
+        .find(|&id| bus.device_as::<T>(id).is_some())
+}
+
+/// Bit 7 to bit 0 as lit or unlit dots
+fn bits(value: u8, lit: Color) -> Vec<Span<'static>> {
+    (0..8)
+        .rev()
+        .map(|bit| {
+            if value & (1 << bit) != 0 {
+                Span::default().content("●").fg(lit)
+            } else {
+                Span::default().content("○").fg(Color::DarkGray)
+            }
+        })
+        .collect()
+}
+
+fn address_range(range: &RangeInclusive<u8>) -> String {
+    if range.start() == range.end() {
+        format!("{:02x}", range.start())
+    } else {
+        format!("{:02x}-{:02x}", range.start(), range.end())
+    }
+}
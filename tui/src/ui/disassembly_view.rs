@@ -0,0 +1,42 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Stylize},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use emulator::Emulator;
+
+/// Lists the instructions from PC onwards
+pub fn disassembly_view(program: &Emulator, area: Rect, buf: &mut Buffer) {
+    let rows = area.height.saturating_sub(2);
+    let mut adr = program.reg_pc().get();
+    let mut lines: Vec<Line> = Vec::with_capacity(rows as usize);
+    for row in 0..rows {
+        let (text, size) = match program.disassemble(adr) {
+            Some(ins) => (ins.to_string(), ins.size()),
+            None => (format!("FCB ${:02X}", program.memory_at(adr)), 1),
+        };
+        let address = Span::default()
+            .content(format!("{:02x} ", adr))
+            .fg(Color::DarkGray);
+        let text = if row == 0 {
+            Span::default()
+                .content(text)
+                .bg(Color::White)
+                .fg(Color::Black)
+        } else {
+            Span::raw(text)
+        };
+        lines.push(Line::from(vec![address, text]));
+        adr = adr.wrapping_add(size);
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .title(Line::from("Code"));
+    Paragraph::new(lines).block(block).render(area, buf);
+}
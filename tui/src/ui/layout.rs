@@ -5,27 +5,38 @@ use ratatui::{
     prelude::{Buffer, Rect},
     widgets::Widget,
 };
-use std::io;
+use std::{io, path::PathBuf};
 
-use emulator::Emulator;
+use emulator::{Emulator, devices::Console};
 
 use crate::{
     event::handle_event,
     ui::{
-        clock_cycles_view::clock_cycles_view, flags_view::flags_view, logs_view::logs_view,
-        memory_view::memory_view, register_view::register_view,
+        clock_cycles_view::clock_cycles_view, console_view::console_view,
+        devices_view::devices_view, disassembly_view::disassembly_view, find_device,
+        flags_view::flags_view, logs_view::logs_view, memory_view::memory_view,
+        register_view::register_view,
     },
 };
 
 pub struct EmulatorVisualizer<'a> {
     pub program: &'a mut Emulator,
+    /// Where machine state is saved to and loaded from
+    pub state_path: PathBuf,
+    /// The next hex digit typed goes to the keypad
+    pub(crate) keypad_entry: bool,
+    /// Keys typed go to the console
+    pub(crate) console_input: bool,
     exit: bool,
 }
 
 impl<'a> EmulatorVisualizer<'a> {
-    pub fn viz(program: &'a mut Emulator) -> io::Result<()> {
+    pub fn viz(program: &'a mut Emulator, state_path: PathBuf) -> io::Result<()> {
         let mut visualizer = Self {
             program,
+            state_path,
+            keypad_entry: false,
+            console_input: false,
             exit: false,
         };
         let mut terminal = ratatui::init();
@@ -63,10 +74,19 @@ impl<'a> Widget for &EmulatorVisualizer<'a> {
         ])
         .areas(area);
 
-        let [registers_area, flags_area, clk_area] = Layout::vertical([
+        let device_rows = self.program.bus().devices().count() as u16;
+        let [
+            registers_area,
+            flags_area,
+            clk_area,
+            code_area,
+            devices_area,
+        ] = Layout::vertical([
             Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Length(3),
+            Constraint::Length(12),
+            Constraint::Length(if device_rows > 0 { device_rows + 2 } else { 0 }),
         ])
         .areas(col1);
 
@@ -74,6 +94,19 @@ impl<'a> Widget for &EmulatorVisualizer<'a> {
         register_view(self.program, registers_area, buf);
         flags_view(self.program, flags_area, buf);
         clock_cycles_view(self.program, clk_area, buf);
-        logs_view(self.program, col2, buf);
+        disassembly_view(self.program, code_area, buf);
+        if device_rows > 0 {
+            devices_view(self.program, devices_area, buf);
+        }
+        let logs_area = match find_device::<Console>(self.program) {
+            Some(console) => {
+                let [console_area, logs_area] =
+                    Layout::vertical([Constraint::Length(12), Constraint::Min(1)]).areas(col2);
+                console_view(self.program, console, self.console_input, console_area, buf);
+                logs_area
+            }
+            None => col2,
+        };
+        logs_view(self.program, logs_area, buf);
     }
 }
@@ -59,6 +59,8 @@ fn render_memory_area(program: &Emulator, area: Rect, buf: &mut Buffer) {
             let s = format!("{:02x}", b);
             let span = if b_idx == pc {
                 Span::default().content(s).bg(Color::White).fg(Color::Black)
+            } else if program.breakpoints().contains(b_idx) {
+                Span::default().content(s).bg(Color::Red).fg(Color::White)
             } else if b == 0 {
                 Span::default().content(s).fg(Color::DarkGray)
             } else {
@@ -1,8 +1,12 @@
 mod clock_cycles_view;
+mod console_view;
+mod devices_view;
+mod disassembly_view;
 mod flags_view;
 mod layout;
 mod logs_view;
 mod memory_view;
 mod register_view;
 
+pub(crate) use devices_view::find_device;
 pub use layout::*;